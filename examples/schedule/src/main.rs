@@ -9,6 +9,7 @@ use std::sync::Arc;
 use std::thread;
 use std::time::Instant;
 
+use gas::chromosone::Fixed;
 use gas::constraints::{self, ConstraintConfig};
 use gas::crossover::{self, CrossoverConfig};
 use gas::fitness::{self, FitnessConfig};
@@ -16,35 +17,36 @@ use gas::game;
 use gas::gas::Gas;
 use gas::mutation::{self, MutationConfig};
 use gas::pool::Pool;
+use gas::rando::Rando;
 use gas::tournaments;
 
 const NTHREADS: usize = 4;
 
 fn main() {
+    let fitness = FitnessConfig::new(vec![
+        Box::new(fitness::distance::Distance::new(
+            7,
+            schedule_data::DISTANCE_BEFORE.to_vec(),
+            vec![None; NSYMS],
+            1.0,
+            1.0,
+        )),
+        Box::new(fitness::color_count::ColorCount::new(
+            schedule_data::NCOLORS,
+            schedule_data::CHROMOSONE_COLORS.to_vec(),
+            schedule_data::COLOR_PREFS
+                .iter()
+                .map(|a| a.to_vec())
+                .collect(),
+            &schedule_data::COLOR_NAMES,
+            1.0,
+        )),
+        Box::new(fitness::weighted_count::WeightedCount::new(
+            schedule_data::MAX_WEIGHT,
+            schedule_data::WEIGHTS.iter().map(|v| v.to_vec()).collect(),
+        )),
+    ]);
     let gas = Arc::new(Gas {
-        fitness: FitnessConfig::new(vec![
-            Box::new(fitness::distance::Distance::<LENGTH, NSYMS>::new(
-                7,
-                schedule_data::DISTANCE_BEFORE,
-                [None; NSYMS],
-                1.0,
-                1.0,
-            )),
-            Box::new(fitness::color_count::ColorCount::new(
-                schedule_data::NCOLORS,
-                schedule_data::CHROMOSONE_COLORS.to_vec(),
-                schedule_data::COLOR_PREFS
-                    .iter()
-                    .map(|a| a.to_vec())
-                    .collect(),
-                &schedule_data::COLOR_NAMES,
-                1.0,
-            )),
-            Box::new(fitness::weighted_count::WeightedCount::new(
-                schedule_data::MAX_WEIGHT,
-                schedule_data::WEIGHTS.iter().map(|v| v.to_vec()).collect(),
-            )),
-        ]),
         constraints: ConstraintConfig::new(vec![Box::new(
             constraints::invalid_position::InvalidPosition::new(
                 schedule_data::INVALID_POSITIONS
@@ -66,22 +68,29 @@ fn main() {
         ]),
         mutations: MutationConfig::new(vec![
             (10, Box::new(mutation::null::Null::new())),
-            (1, Box::new(mutation::mutate::Mutate::new(1))),
-            (1, Box::new(mutation::mutate::Mutate::new(2))),
-            (1, Box::new(mutation::mutate::Mutate::new(3))),
+            (1, Box::new(mutation::mutate::Mutate::new(1, NSYMS))),
+            (1, Box::new(mutation::mutate::Mutate::new(2, NSYMS))),
+            (1, Box::new(mutation::mutate::Mutate::new(3, NSYMS))),
             (1, Box::new(mutation::rotate::Rotate::new(1))),
             (1, Box::new(mutation::rotate::Rotate::new(2))),
             (1, Box::new(mutation::rotate::Rotate::new(3))),
         ]),
         taboo_distance: 5,
         population_size: 200,
+        ..Gas::new(Fixed::<LENGTH, NSYMS>::new(), fitness)
     });
 
     let sigint = Arc::new(AtomicBool::new(false));
     signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(&sigint)).unwrap();
     let start = Instant::now();
 
-    let mut pool = Pool::new(gas.clone(), NTHREADS, sigint);
+    // pass a seed on the command line to reproduce a previous run
+    let rng = match std::env::args().nth(1) {
+        Some(seed) => Rando::seed_from_u64(seed.parse().unwrap()),
+        None => Rando::new(),
+    };
+
+    let mut pool = Pool::new(gas.clone(), NTHREADS, sigint, rng);
 
     while !pool.is_finished() {
         for i in 0..NTHREADS {
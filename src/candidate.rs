@@ -1,19 +1,29 @@
-use crate::chromosone::Gene;
+use crate::chromosone::{GeneType, Shape};
 use crate::gas::Gas;
-use array_init::array_init;
+use std::borrow::Borrow;
+
+#[cfg(doc)]
+use crate::game::Game;
 
 #[cfg_attr(test, mockall_double::double)]
 use crate::rando::Rando;
 
 #[derive(Debug, PartialEq, Clone)]
-pub struct Candidate<const N: usize, const NSYMS: usize> {
-    pub chromosone: [Gene; N],
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct Candidate<S: Shape> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::chromosone::serde_chromosone"))]
+    pub chromosone: S::Chromosone,
+    #[cfg_attr(feature = "serde", serde(with = "serde_scores"))]
     pub scores: Vec<f64>,
     pub violations: usize,
 }
 
-impl<const N: usize, const NSYMS: usize> Candidate<N, NSYMS> {
-    pub fn from_chromosone(gas: &Gas<N, NSYMS>, chromosone: [Gene; N]) -> Candidate<N, NSYMS> {
+impl<S: Shape> Candidate<S> {
+    pub fn from_chromosone(gas: &Gas<S>, chromosone: S::Chromosone) -> Candidate<S> {
         let scores = gas.fitness.scores(&chromosone);
         let violations = gas.constraints.violations(&chromosone);
         Candidate {
@@ -23,27 +33,115 @@ impl<const N: usize, const NSYMS: usize> Candidate<N, NSYMS> {
         }
     }
 
-    pub fn new(gas: &Gas<N, NSYMS>, rng: &mut Rando) -> Candidate<N, NSYMS> {
-        let mut rand_iter = rng.uniform_iter(0..NSYMS);
-        Candidate::from_chromosone(gas, array_init(|_| rand_iter.next().unwrap() as Gene))
+    /// the same as [Candidate::from_chromosone], but calculated incrementally from a parent using [FitnessConfig::scores_delta](crate::fitness::FitnessConfig::scores_delta) and [ConstraintConfig::violations_delta](crate::constraints::ConstraintConfig::violations_delta).   Cheaper when the chromosone differs from the parent at only a few loci.
+    pub fn from_parent(
+        gas: &Gas<S>,
+        chromosone: S::Chromosone,
+        parent: &Candidate<S>,
+    ) -> Candidate<S> {
+        let changed: Vec<usize> = (0..gas.shape.length())
+            .filter(|i| chromosone[*i] != parent.chromosone[*i])
+            .collect();
+        let scores =
+            gas.fitness
+                .scores_delta(&chromosone, &parent.chromosone, &parent.scores, &changed);
+        let violations = gas.constraints.violations_delta(
+            &chromosone,
+            &parent.chromosone,
+            parent.violations,
+            &changed,
+        );
+        Candidate {
+            chromosone,
+            scores,
+            violations,
+        }
+    }
+
+    /// a random candidate.   If [Gas::domain] is set, each gene is drawn from the symbols allowed at its locus.
+    pub fn new(gas: &Gas<S>, rng: &mut Rando) -> Candidate<S> {
+        let chromosone = match &gas.domain {
+            Some(domain) => gas.shape.chromosone(|locus| {
+                let symbols = domain.symbols(locus);
+                S::Gene::from_usize(symbols[rng.gen_range(0..symbols.len())])
+            }),
+            None => {
+                let mut rand_iter = rng.uniform_iter(0..gas.shape.nsyms());
+                gas.shape
+                    .chromosone(|_| S::Gene::from_usize(rand_iter.next().unwrap()))
+            }
+        };
+        Candidate::from_chromosone(gas, chromosone)
     }
 
     #[cfg_attr(test, allow(dead_code))]
-    /// calculate an aggregate score.  The system doesn't use this internally, but it can be used for a very rough comparison between candidates.
+    /// calculate an aggregate score: the sum of the scores multiplied by their weights, skipping NaN's.   A very rough comparison between candidates, used for the [CycleStats](crate::gas::cycle::CycleStats) and by [Candidate::beats].
     pub fn total_score(&self, weights: &Vec<f64>) -> f64 {
         assert_eq!(self.scores.len(), weights.len());
         self.scores
             .iter()
-            .filter(|s| !s.is_nan())
             .enumerate()
-            .fold(0.0, |total, (i, score)| total + weights[i] + score)
+            .filter(|(_, s)| !s.is_nan())
+            .fold(0.0, |total, (i, score)| total + weights[i] * score)
+    }
+
+    /// a simple comparison of two candidates: fewer violations wins, and with the same number of violations the higher [Candidate::total_score] wins.   The [Game]'s are more nuanced.
+    pub fn beats(&self, other: &Candidate<S>, weights: &Vec<f64>) -> bool {
+        self.violations < other.violations
+            || (self.violations == other.violations
+                && self.total_score(weights) > other.total_score(weights))
+    }
+
+    /// Pareto dominance over the scores vector: fewer violations dominates, and with the same number of violations `self` dominates if it is at least as good on every score and better on at least one.   A NaN score is worse than any other.
+    pub fn dominates(&self, other: &Candidate<S>) -> bool {
+        if self.violations != other.violations {
+            return self.violations < other.violations;
+        }
+        let mut better = false;
+        for (a, b) in std::iter::zip(&self.scores, &other.scores) {
+            let (a, b) = (nan_lowest(*a), nan_lowest(*b));
+            if a < b {
+                return false;
+            }
+            better |= a > b;
+        }
+        better
+    }
+
+    /// the NSGA-II crowding distance of each candidate: how far apart its neighbours are on each score, normalized by the range of the score and summed.   The candidates at the ends of each score are infinitely far from the crowd.
+    pub fn crowding<C: Borrow<Candidate<S>>>(population: &[C]) -> Vec<f64> {
+        let n = population.len();
+        let mut distances = vec![0.0; n];
+        if n == 0 {
+            return distances;
+        }
+        let score = |i: usize, s: usize| nan_lowest(population[i].borrow().scores[s]);
+        for s in 0..population[0].borrow().scores.len() {
+            let mut order: Vec<usize> = (0..n).collect();
+            order.sort_by(|a, b| score(*a, s).total_cmp(&score(*b, s)));
+            distances[order[0]] = f64::INFINITY;
+            distances[order[n - 1]] = f64::INFINITY;
+            let range = score(order[n - 1], s) - score(order[0], s);
+            if range.is_finite() && range > 0.0 {
+                for k in 1..n - 1 {
+                    distances[order[k]] +=
+                        (score(order[k + 1], s) - score(order[k - 1], s)) / range;
+                }
+            }
+        }
+        distances
     }
 
     /// Hamming distance
-    pub fn distance(&self, other: &Candidate<N, NSYMS>) -> usize {
+    pub fn distance(&self, other: &Candidate<S>) -> usize {
+        Candidate::<S>::hamming(&self.chromosone, &other.chromosone)
+    }
+
+    /// Hamming distance between two chromosones
+    pub fn hamming(left: &S::Chromosone, right: &S::Chromosone) -> usize {
         let mut count = 0usize;
-        for i in 0..self.chromosone.len() {
-            if self.chromosone[i] != other.chromosone[i] {
+        for i in 0..left.as_ref().len() {
+            if left[i] != right[i] {
                 count += 1;
             }
         }
@@ -52,19 +150,20 @@ impl<const N: usize, const NSYMS: usize> Candidate<N, NSYMS> {
 
     #[allow(dead_code)]
     /// give an estimate of a population's diversity where 1 == all the same and 0 == completely different.   Calculation is similar to a Hamming distance.
-    pub fn similarity(population: &[Candidate<N, NSYMS>]) -> f64 {
-        let mut similarities = Vec::<f64>::with_capacity(population[0].chromosone.len());
-        for i in 0..population[0].chromosone.len() {
-            let mut map = std::collections::HashMap::<usize, usize>::new();
+    pub fn similarity(population: &[Candidate<S>]) -> f64 {
+        let len = population[0].chromosone.as_ref().len();
+        let mut similarities = Vec::<f64>::with_capacity(len);
+        for i in 0..len {
+            let mut map = std::collections::HashMap::<S::Gene, usize>::new();
             let mut max_count = 0usize;
             for j in 0..population.len() {
-                let count = match map.get_mut(&population[j].chromosone[i].into()) {
+                let count = match map.get_mut(&population[j].chromosone[i]) {
                     Some(v) => {
                         *v += 1;
                         *v
                     }
                     None => {
-                        map.insert(population[j].chromosone[i].into(), 1);
+                        map.insert(population[j].chromosone[i], 1);
                         1usize
                     }
                 };
@@ -79,7 +178,7 @@ impl<const N: usize, const NSYMS: usize> Candidate<N, NSYMS> {
 
     #[cfg(test)]
     /// cannot use assert_eq! on scores because they sometimes contain NaN.  NaN != NaN, which is true mathematically, but sucks in unit tests
-    pub fn assert_eq(left: &Candidate<N, NSYMS>, right: &Candidate<N, NSYMS>) {
+    pub fn assert_eq(left: &Candidate<S>, right: &Candidate<S>) {
         assert_eq!(
             left.chromosone, right.chromosone,
             "left {:?} right {:?}",
@@ -94,9 +193,41 @@ impl<const N: usize, const NSYMS: usize> Candidate<N, NSYMS> {
     }
 }
 
+/// `score`, with NaN ranked below every other score
+fn nan_lowest(score: f64) -> f64 {
+    if score.is_nan() {
+        f64::NEG_INFINITY
+    } else {
+        score
+    }
+}
+
+/// NaN is a valid score, but JSON cannot represent it, so scores are (de)serialized as `Option<f64>` with NaN as `None`.
+#[cfg(feature = "serde")]
+mod serde_scores {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(scores: &[f64], serializer: S) -> Result<S::Ok, S::Error> {
+        scores
+            .iter()
+            .map(|s| if s.is_nan() { None } else { Some(*s) })
+            .collect::<Vec<Option<f64>>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f64>, D::Error> {
+        Ok(Vec::<Option<f64>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|s| s.unwrap_or(f64::NAN))
+            .collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chromosone::Fixed;
+    use crate::domain::Domain;
     use mockall::*;
 
     #[test]
@@ -114,6 +245,16 @@ mod tests {
         assert_eq!(candidate.scores.len(), gas.fitness.weights().len());
     }
 
+    #[test]
+    fn test_from_parent() {
+        let gas = Gas::dut();
+        let parent = Candidate::from_chromosone(&gas, [0, 0, 1, 0, 1]);
+        Candidate::assert_eq(
+            &Candidate::from_parent(&gas, [0, 2, 1, 0, 1], &parent),
+            &Candidate::from_chromosone(&gas, [0, 2, 1, 0, 1]),
+        );
+    }
+
     #[test]
     fn test_similarity() {
         let gas = Gas::dut();
@@ -153,6 +294,16 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let gas = Gas::dut();
+        let candidate = Candidate::from_chromosone(&gas, [0, 0, 0, 0, 0]);
+        assert!(candidate.scores.iter().any(|s| s.is_nan()));
+        let json = serde_json::to_string(&candidate).unwrap();
+        Candidate::assert_eq(&serde_json::from_str(&json).unwrap(), &candidate);
+    }
+
     #[test]
     fn test_new() {
         let gas = Gas::dut();
@@ -170,4 +321,67 @@ mod tests {
             },
         );
     }
+    #[test]
+    fn test_new_within_domain() {
+        let mut gas = Gas::dut();
+        gas.domain = Some(Domain::new(vec![
+            vec![0],
+            vec![1, 2],
+            vec![2],
+            vec![0],
+            vec![0],
+        ]));
+        let mut r = Rando::default();
+        r.expect_gen_range()
+            .with(predicate::eq(0..1))
+            .times(4)
+            .return_const(0usize);
+        r.expect_gen_range()
+            .with(predicate::eq(0..2))
+            .times(1)
+            .return_const(1usize);
+        assert_eq!(Candidate::new(&gas, &mut r).chromosone, [0, 2, 2, 0, 0]);
+    }
+    #[test]
+    fn test_dominates() {
+        let c = |scores: Vec<f64>, violations| Candidate::<Fixed<5, 3>> {
+            chromosone: [0, 0, 0, 0, 0],
+            scores,
+            violations,
+        };
+        assert!(c(vec![1.0, 2.0], 0).dominates(&c(vec![1.0, 1.0], 0)));
+        assert!(!c(vec![1.0, 2.0], 0).dominates(&c(vec![1.0, 2.0], 0)));
+        assert!(!c(vec![2.0, 1.0], 0).dominates(&c(vec![1.0, 2.0], 0)));
+        assert!(c(vec![0.0, 0.0], 0).dominates(&c(vec![9.0, 9.0], 1)));
+        assert!(c(vec![0.0, 0.0], 0).dominates(&c(vec![0.0, f64::NAN], 0)));
+
+        let population = vec![
+            c(vec![0.0, 4.0], 0),
+            c(vec![1.0, 3.0], 0),
+            c(vec![3.0, 1.0], 0),
+            c(vec![4.0, 0.0], 0),
+        ];
+        let crowding = Candidate::crowding(&population);
+        assert_eq!(crowding[0], f64::INFINITY);
+        assert_eq!(crowding[1], 1.5);
+        assert_eq!(crowding[2], 1.5);
+        assert_eq!(crowding[3], f64::INFINITY);
+    }
+
+    #[test]
+    fn test_total_score() {
+        let c = |scores: Vec<f64>, violations| Candidate::<Fixed<5, 3>> {
+            chromosone: [0, 0, 0, 0, 0],
+            scores,
+            violations,
+        };
+        let weights = vec![1.0, 2.0, 10.0];
+        assert_eq!(c(vec![3.0, 1.0, 0.5], 0).total_score(&weights), 10.0);
+        // the NaN is skipped without moving the later weights onto the wrong scores
+        assert_eq!(c(vec![f64::NAN, 1.0, 0.5], 0).total_score(&weights), 7.0);
+
+        // the heavily weighted last score decides
+        assert!(c(vec![0.0, 0.0, 1.0], 0).beats(&c(vec![5.0, 2.0, 0.0], 0), &weights));
+        assert!(!c(vec![0.0, 0.0, 1.0], 1).beats(&c(vec![5.0, 2.0, 0.0], 0), &weights));
+    }
 }
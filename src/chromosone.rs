@@ -1,2 +1,282 @@
-/// In all use cases so far, NSYMS has been less than 256 so Gene fits in a u8.  If you need a bigger Gene you can redefine it here or bug me to plumb Gene through the generic type system.
+use array_init::array_init;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+
+/// The default [GeneType].   In all use cases so far, NSYMS has been less than 256 so a gene fits in a u8.
 pub type Gene = u8;
+
+/**
+The integer type a gene is stored in.   Implemented for `u8`, `u16` and `u32`.
+
+Every [Shape] picks one, [Gene] by default.   Pick a wider one when there are more than 256 symbols.   A [Fixed] shape whose `NSYMS` doesn't fit fails to compile, and a [Dynamic] one panics when it is constructed.
+ */
+pub trait GeneType: Copy + Debug + Default + Eq + Hash + Ord + Send + Sync + 'static {
+    /// the number of distinct symbols this type can hold
+    const SYMBOLS: usize;
+
+    /// the gene for symbol `symbol`.   Panics if it isn't less than [GeneType::SYMBOLS], rather than silently wrapping.
+    fn from_usize(symbol: usize) -> Self;
+
+    /// the symbol this gene holds
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! gene_type {
+    ($($t:ty),*) => {$(
+        impl GeneType for $t {
+            const SYMBOLS: usize = (<$t>::MAX as usize).saturating_add(1);
+
+            fn from_usize(symbol: usize) -> Self {
+                <$t>::try_from(symbol).unwrap_or_else(|_| {
+                    panic!("symbol {} doesn't fit in a {} gene", symbol, stringify!($t))
+                })
+            }
+
+            fn to_usize(self) -> usize {
+                self as usize
+            }
+        }
+    )*};
+}
+
+gene_type!(u8, u16, u32);
+
+/**
+The dimensions of a problem: the length of a chromosone and the number of symbols, and how a chromosone is stored.
+
+Virtually every object in the system is generic over a `Shape`.   Use [Fixed] when the dimensions are known at compile time, and [Dynamic] when they only become known at run time, for instance when they come from a request to a service.   Both share all the same operators, fitness functions, games and tournaments.
+ */
+pub trait Shape: Copy + Debug + PartialEq + Send + Sync + 'static {
+    /// the type of a single gene
+    type Gene: GeneType;
+
+    /// how a chromosone of this shape is stored.   Genes are in the range `0..nsyms()`.
+    type Chromosone: Clone
+        + Debug
+        + PartialEq
+        + Send
+        + Sync
+        + AsRef<[Self::Gene]>
+        + AsMut<[Self::Gene]>
+        + Index<usize, Output = Self::Gene>
+        + IndexMut<usize>
+        + TryFrom<Vec<Self::Gene>>;
+
+    /// the length of a chromosone
+    fn length(&self) -> usize;
+
+    /// the number of genetic symbols
+    fn nsyms(&self) -> usize;
+
+    /// a chromosone with gene `genes(locus)` at each locus
+    fn chromosone(&self, genes: impl FnMut(usize) -> Self::Gene) -> Self::Chromosone;
+}
+
+/**
+A shape known at compile time.   `N` is the length of the chromosone, and `NSYMS` the number of symbols.   A chromosone is a `[G; N]`.
+
+```compile_fail
+# use gas::chromosone::{Fixed, Shape};
+// 300 symbols don't fit in the default u8
+Fixed::<2, 300>::new().nsyms();
+```
+
+```
+# use gas::chromosone::{Fixed, Shape};
+assert_eq!(Fixed::<2, 300, u16>::new().nsyms(), 300);
+```
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Fixed<const N: usize, const NSYMS: usize, G: GeneType = Gene>(PhantomData<G>);
+
+impl<const N: usize, const NSYMS: usize, G: GeneType> Fixed<N, NSYMS, G> {
+    /// evaluated whenever the shape is used, so a `NSYMS` that doesn't fit in `G` is a compile error
+    const FITS: () = assert!(NSYMS <= G::SYMBOLS, "NSYMS doesn't fit in the gene type");
+
+    pub const fn new() -> Self {
+        Fixed(PhantomData)
+    }
+}
+
+impl<const N: usize, const NSYMS: usize, G: GeneType> Shape for Fixed<N, NSYMS, G> {
+    type Gene = G;
+    type Chromosone = [G; N];
+
+    fn length(&self) -> usize {
+        N
+    }
+
+    fn nsyms(&self) -> usize {
+        let () = Self::FITS;
+        NSYMS
+    }
+
+    fn chromosone(&self, genes: impl FnMut(usize) -> G) -> [G; N] {
+        let () = Self::FITS;
+        array_init(genes)
+    }
+}
+
+/**
+A shape only known at run time.   A chromosone is a `Vec<G>` of length `length`.
+
+```
+# use gas::Gas;
+# use gas::chromosone::Dynamic;
+# use gas::fitness::{self, FitnessConfig};
+# use gas::gas::cycle::CycleProgress;
+# use gas::mutation::{self, MutationConfig};
+# use gas::rando::Rando;
+# use gas::stopping::*;
+# use std::sync::atomic::AtomicBool;
+# use std::sync::Arc;
+// the size of the problem isn't known until it arrives
+let (length, nsyms) = (4, 6);
+// gene i is worth 1.0 at locus i
+let desirability = (0..nsyms).map(|g| (0..length).map(|i| if g == i { 1.0 } else { 0.0 }).collect()).collect();
+let gas = Gas {
+  mutations: MutationConfig::new(vec![(1, Box::new(mutation::Mutate::new(1, nsyms)))]),
+#  stopping: StoppingConfig {
+#    stop: Box::new(MaxGenerations::new(200)),
+#    ..StoppingConfig::default()
+#  },
+  ..Gas::new(
+    Dynamic::new(length, nsyms),
+    FitnessConfig::new(vec![Box::new(fitness::LocusDesirability::new(desirability, 1.0))]),
+  )
+};
+let sigint = Arc::new(AtomicBool::new(false));
+let winner = gas.cycle(&mut CycleProgress::new(&gas, &sigint), Rando::seed_from_u64(1));
+assert_eq!(winner.chromosone, vec![0, 1, 2, 3]);
+```
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dynamic<G: GeneType = Gene> {
+    pub length: usize,
+    pub nsyms: usize,
+    gene: PhantomData<G>,
+}
+
+impl Dynamic {
+    /// a shape with the default [Gene].   Panics if `nsyms` doesn't fit in it.
+    pub fn new(length: usize, nsyms: usize) -> Dynamic {
+        Dynamic::with_gene(length, nsyms)
+    }
+}
+
+impl<G: GeneType> Dynamic<G> {
+    /// a shape whose genes are a `G`, e.g. `Dynamic::<u16>::with_gene(length, 1000)`.   Panics if `nsyms` doesn't fit in a `G`.
+    pub fn with_gene(length: usize, nsyms: usize) -> Dynamic<G> {
+        assert!(
+            nsyms <= G::SYMBOLS,
+            "{} symbols don't fit in a {}",
+            nsyms,
+            std::any::type_name::<G>()
+        );
+        Dynamic {
+            length,
+            nsyms,
+            gene: PhantomData,
+        }
+    }
+}
+
+impl<G: GeneType> Shape for Dynamic<G> {
+    type Gene = G;
+    type Chromosone = Vec<G>;
+
+    fn length(&self) -> usize {
+        self.length
+    }
+
+    fn nsyms(&self) -> usize {
+        self.nsyms
+    }
+
+    fn chromosone(&self, genes: impl FnMut(usize) -> G) -> Vec<G> {
+        (0..self.length).map(genes).collect()
+    }
+}
+
+/// serde only supports arrays of up to 32 elements, so chromosones are (de)serialized as sequences of symbols.  Use with `#[serde(with = "crate::chromosone::serde_chromosone")]`.
+#[cfg(feature = "serde")]
+pub(crate) mod serde_chromosone {
+    use super::GeneType;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<Ser: Serializer, G: GeneType, C: AsRef<[G]>>(
+        chromosone: &C,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_seq(chromosone.as_ref().iter().map(|g| g.to_usize()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, G: GeneType, C: TryFrom<Vec<G>>>(
+        deserializer: D,
+    ) -> Result<C, D::Error> {
+        let symbols = Vec::<usize>::deserialize(deserializer)?;
+        if let Some(s) = symbols.iter().find(|s| **s >= G::SYMBOLS) {
+            return Err(serde::de::Error::custom(format!(
+                "symbol {} doesn't fit in the gene type",
+                s
+            )));
+        }
+        let v: Vec<G> = symbols.into_iter().map(G::from_usize).collect();
+        let len = v.len();
+        C::try_from(v).map_err(|_| {
+            serde::de::Error::invalid_length(len, &"a chromosone of the configured length")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shapes() {
+        let fixed = Fixed::<3, 4>::new();
+        assert_eq!(fixed.chromosone(Gene::from_usize), [0, 1, 2]);
+        let dynamic = Dynamic::new(3, 4);
+        assert_eq!(dynamic.chromosone(Gene::from_usize), vec![0, 1, 2]);
+        assert_eq!(
+            (dynamic.length(), dynamic.nsyms()),
+            (fixed.length(), fixed.nsyms())
+        );
+    }
+
+    #[test]
+    fn test_gene_types() {
+        let wide = Fixed::<2, 1000, u16>::new();
+        assert_eq!(wide.chromosone(|i| u16::from_usize(999 - i)), [999, 998]);
+        assert_eq!(Dynamic::<u32>::with_gene(2, 70000).nsyms(), 70000);
+        assert_eq!(u8::SYMBOLS, 256);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gene_too_narrow() {
+        Dynamic::new(2, 257);
+    }
+
+    #[test]
+    #[should_panic(expected = "symbol 299 doesn't fit in a u8 gene")]
+    fn test_mutate_too_wide() {
+        use crate::mutation::{Mutate, Mutation};
+        use mockall::predicate;
+
+        let mut r = crate::rando::MockRando::default();
+        r.expect_gen_range()
+            .with(predicate::eq(0..3))
+            .times(1)
+            .return_const(0usize);
+        r.expect_gen_range()
+            .with(predicate::eq(0..300))
+            .times(1)
+            .return_const(299usize);
+        let m: &dyn Mutation<Fixed<3, 4>> = &Mutate::new(1, 300);
+        m.run(&[0, 1, 2], &mut r);
+    }
+}
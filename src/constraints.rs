@@ -1,4 +1,4 @@
-use crate::chromosone::Gene;
+use crate::chromosone::Shape;
 
 pub mod invalid_position;
 pub use invalid_position::InvalidPosition;
@@ -9,24 +9,49 @@ Constraints are used to filter out nonviable chromosone's.   In other words, a c
 
 */
 
-pub trait Constraint<const N: usize, const NSYMS: usize> {
-    fn run(&self, chromosone: &[Gene; N]) -> usize;
+pub trait Constraint<S: Shape> {
+    fn run(&self, chromosone: &S::Chromosone) -> usize;
+    /// optional: the change in violations between `parent` and `chromosone`, which differ only at the loci listed in `changed`.   Return None if it can't be calculated more cheaply than [Constraint::run], which is the default.   If any constraint returns None, all constraints are recalculated from scratch.
+    fn run_delta(
+        &self,
+        _chromosone: &S::Chromosone,
+        _parent: &S::Chromosone,
+        _changed: &[usize],
+    ) -> Option<isize> {
+        None
+    }
 }
 
-pub struct ConstraintConfig<const N: usize, const NSYMS: usize> {
-    pub constraints: Vec<Box<dyn Constraint<N, NSYMS> + Sync + Send>>,
+pub struct ConstraintConfig<S: Shape> {
+    pub constraints: Vec<Box<dyn Constraint<S> + Sync + Send>>,
 }
 
-impl<const N: usize, const NSYMS: usize> ConstraintConfig<N, NSYMS> {
-    pub fn new(
-        constraints: Vec<Box<dyn Constraint<N, NSYMS> + Sync + Send>>,
-    ) -> ConstraintConfig<N, NSYMS> {
+impl<S: Shape> ConstraintConfig<S> {
+    pub fn new(constraints: Vec<Box<dyn Constraint<S> + Sync + Send>>) -> ConstraintConfig<S> {
         ConstraintConfig { constraints }
     }
 
-    pub fn violations(&self, chromosone: &[Gene; N]) -> usize {
+    pub fn violations(&self, chromosone: &S::Chromosone) -> usize {
         self.constraints
             .iter()
             .fold(0usize, |sum, cf| sum + cf.run(chromosone))
     }
+
+    /// like [ConstraintConfig::violations], but using [Constraint::run_delta] with the violations of a parent that differs only at the `changed` loci.
+    pub fn violations_delta(
+        &self,
+        chromosone: &S::Chromosone,
+        parent: &S::Chromosone,
+        parent_violations: usize,
+        changed: &[usize],
+    ) -> usize {
+        let mut violations = parent_violations as isize;
+        for cf in self.constraints.iter() {
+            match cf.run_delta(chromosone, parent, changed) {
+                Some(delta) => violations += delta,
+                None => return self.violations(chromosone),
+            }
+        }
+        violations as usize
+    }
 }
@@ -1,5 +1,6 @@
 use super::Constraint;
-use crate::chromosone::Gene;
+use crate::chromosone::{GeneType, Shape};
+use crate::domain::Domain;
 
 /**
 
@@ -7,35 +8,68 @@ This constraint allows the specification of which positions in the chromosone ar
 
  */
 
-pub struct InvalidPosition<const N: usize, const NSYMS: usize> {
+pub struct InvalidPosition {
     pub invalid_positions: Vec<Vec<bool>>, // FIXME [[bool; N]; NSYMS]
 }
 
-impl<const N: usize, const NSYMS: usize> InvalidPosition<N, NSYMS> {
-    pub const fn new(invalid_positions: Vec<Vec<bool>>) -> InvalidPosition<N, NSYMS> {
+impl InvalidPosition {
+    pub const fn new(invalid_positions: Vec<Vec<bool>>) -> InvalidPosition {
         InvalidPosition { invalid_positions }
     }
+
+    /// the [Domain] of the symbols that are valid at each locus.   A locus where every symbol is invalid can't be satisfied anyway, so it allows them all.
+    pub fn domain(&self) -> Domain {
+        let nsyms = self.invalid_positions.len();
+        let len = self.invalid_positions.first().map_or(0, |p| p.len());
+        Domain::new(
+            (0..len)
+                .map(|locus| {
+                    let valid: Vec<usize> = (0..nsyms)
+                        .filter(|g| !self.invalid_positions[*g][locus])
+                        .collect();
+                    if valid.is_empty() {
+                        (0..nsyms).collect()
+                    } else {
+                        valid
+                    }
+                })
+                .collect(),
+        )
+    }
 }
 
-impl<const N: usize, const NSYMS: usize> Constraint<N, NSYMS> for InvalidPosition<N, NSYMS> {
-    fn run(&self, chromosone: &[Gene; N]) -> usize {
+impl<S: Shape> Constraint<S> for InvalidPosition {
+    fn run(&self, chromosone: &S::Chromosone) -> usize {
         let mut violations: usize = 0;
-        for (i, g) in chromosone.iter().enumerate() {
-            if self.invalid_positions[*g as usize][i] {
+        for (i, g) in chromosone.as_ref().iter().enumerate() {
+            if self.invalid_positions[g.to_usize()][i] {
                 violations += 1;
             }
         }
         violations
     }
+
+    fn run_delta(
+        &self,
+        chromosone: &S::Chromosone,
+        parent: &S::Chromosone,
+        changed: &[usize],
+    ) -> Option<isize> {
+        Some(changed.iter().fold(0isize, |delta, &i| {
+            delta + self.invalid_positions[chromosone[i].to_usize()][i] as isize
+                - self.invalid_positions[parent[i].to_usize()][i] as isize
+        }))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chromosone::Fixed;
 
     #[test]
     fn test_invalid_position() {
-        let c = InvalidPosition::<5, 3>::new(vec![
+        let c: &dyn Constraint<Fixed<5, 3>> = &InvalidPosition::new(vec![
             vec![false, false, false, false, false],
             vec![false, true, false, true, false],
             vec![true, true, true, true, true],
@@ -44,5 +78,22 @@ mod tests {
         assert_eq!(c.run(&[0, 1, 0, 0, 0]), 1);
         assert_eq!(c.run(&[1, 1, 1, 1, 1]), 2);
         assert_eq!(c.run(&[2, 0, 2, 0, 2]), 3);
+        assert_eq!(
+            c.run_delta(&[2, 0, 2, 0, 2], &[0, 1, 0, 0, 0], &[0, 1, 2, 4]),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_domain() {
+        let c = InvalidPosition::new(vec![
+            vec![false, true, true],
+            vec![true, false, true],
+            vec![false, false, true],
+        ]);
+        assert_eq!(
+            c.domain(),
+            Domain::new(vec![vec![0, 2], vec![1, 2], vec![0, 1, 2]])
+        );
     }
 }
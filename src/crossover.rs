@@ -6,58 +6,83 @@ pub use mix::Mix;
 pub use null::Null;
 pub use splice::Splice;
 
-use crate::chromosone::Gene;
+use crate::chromosone::Shape;
+use crate::operator_weights::{Adaptation, OperatorWeights, Selection};
 
 #[mockall_double::double]
 use crate::rando::Rando;
 
 /**
 *  An operator that given two chromosones, produces a third.   Aka breeding.
+*
+*  The crossovers in this module keep every gene at its locus, so a child respects any [Domain](crate::domain::Domain) its parents respect.
 **/
-pub trait Crossover<const N: usize, const NSYMS: usize> {
-    fn run(&self, left: &[Gene; N], right: &[Gene; N], rng: &mut Rando) -> [Gene; N];
+pub trait Crossover<S: Shape> {
+    fn run(&self, left: &S::Chromosone, right: &S::Chromosone, rng: &mut Rando) -> S::Chromosone;
 }
 
-pub struct CrossoverIter<'a, const N: usize, const NSYMS: usize> {
-    i: usize,
-    config: &'a CrossoverConfig<N, NSYMS>,
+pub struct CrossoverIter<'a, S: Shape> {
+    index: usize,
+    indices: Box<dyn Iterator<Item = usize>>,
+    config: &'a CrossoverConfig<S>,
 }
 
-impl<'a, const N: usize, const NSYMS: usize> Iterator for CrossoverIter<'a, N, NSYMS> {
-    type Item = &'a Box<dyn Crossover<N, NSYMS> + Sync + Send>;
+impl<'a, S: Shape> CrossoverIter<'a, S> {
+    /// the index into [CrossoverConfig::crossovers_with_weights] of the operator most recently returned by `next`.   Pass it to [OperatorWeights::record].
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
 
-    fn next(&mut self) -> Option<&'a Box<dyn Crossover<N, NSYMS> + Sync + Send>> {
-        self.i += 1;
-        if self.i >= self.config.indices.len() {
-            self.i = 0;
-        }
-        Some(&self.config.crossovers_with_weights[self.config.indices[self.i]].1)
+impl<'a, S: Shape> Iterator for CrossoverIter<'a, S> {
+    type Item = &'a Box<dyn Crossover<S> + Sync + Send>;
+
+    fn next(&mut self) -> Option<&'a Box<dyn Crossover<S> + Sync + Send>> {
+        self.index = self.indices.next()?;
+        Some(&self.config.crossovers_with_weights[self.index].1)
     }
 }
 
-pub struct CrossoverConfig<const N: usize, const NSYMS: usize> {
-    pub crossovers_with_weights: Vec<(usize, Box<dyn Crossover<N, NSYMS> + Sync + Send>)>,
-    pub indices: Vec<usize>,
+pub struct CrossoverConfig<S: Shape> {
+    pub crossovers_with_weights: Vec<(usize, Box<dyn Crossover<S> + Sync + Send>)>,
+    pub weights: OperatorWeights,
 }
 
-impl<const N: usize, const NSYMS: usize> CrossoverConfig<N, NSYMS> {
+impl<S: Shape> CrossoverConfig<S> {
     pub fn new(
-        crossovers_with_weights: Vec<(usize, Box<dyn Crossover<N, NSYMS> + Sync + Send>)>,
-    ) -> CrossoverConfig<N, NSYMS> {
+        crossovers_with_weights: Vec<(usize, Box<dyn Crossover<S> + Sync + Send>)>,
+    ) -> CrossoverConfig<S> {
         let weights = crossovers_with_weights
             .iter()
             .map(|c| c.0)
             .collect::<Vec<usize>>();
-        let indices = crate::helpers::multidimensional_bresenhams(&weights);
         CrossoverConfig {
-            indices,
+            weights: OperatorWeights::new(weights),
             crossovers_with_weights,
         }
     }
 
-    pub fn iter(&self) -> CrossoverIter<N, NSYMS> {
+    /// like [CrossoverConfig::new], but the weights are only the starting point and adapt to how well each operator does.  See [operator_weights](crate::operator_weights).
+    pub fn adaptive(
+        crossovers_with_weights: Vec<(usize, Box<dyn Crossover<S> + Sync + Send>)>,
+        adaptation: Adaptation,
+    ) -> CrossoverConfig<S> {
+        let mut config = CrossoverConfig::new(crossovers_with_weights);
+        config.weights = OperatorWeights::adaptive(config.weights.weights(), Some(adaptation));
+        config
+    }
+
+    /// choose the operators as described by `selection`.  See [operator_weights](crate::operator_weights#selection).
+    pub fn with_selection(mut self, selection: Selection) -> CrossoverConfig<S> {
+        self.weights.selection = selection;
+        self
+    }
+
+    /// `rng` is only used with [Selection::Random]
+    pub fn iter(&self, rng: &mut Rando) -> CrossoverIter<'_, S> {
         CrossoverIter {
-            i: self.indices.len(),
+            index: 0,
+            indices: self.weights.sequence(rng),
             config: self,
         }
     }
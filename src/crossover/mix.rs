@@ -1,4 +1,4 @@
-use crate::chromosone::Gene;
+use crate::chromosone::Shape;
 use crate::crossover::Crossover;
 
 #[mockall_double::double]
@@ -7,19 +7,19 @@ use crate::rando::Rando;
 /** Mix is a crossover function that mixes the two crossovers on a locus-by-locus basis rather than a splice.
 
 To use a deck of cards analogy, it's a riff rather than a cut **/
-pub struct Mix<const N: usize, const NSYMS: usize> {}
+pub struct Mix {}
 
-impl<const N: usize, const NSYMS: usize> Mix<N, NSYMS> {
+impl Mix {
     pub const fn new() -> Self {
         Self {}
     }
 }
 
-impl<const N: usize, const NSYMS: usize> Crossover<N, NSYMS> for Mix<N, NSYMS> {
-    fn run(&self, left: &[Gene; N], right: &[Gene; N], rng: &mut Rando) -> [Gene; N] {
+impl<S: Shape> Crossover<S> for Mix {
+    fn run(&self, left: &S::Chromosone, right: &S::Chromosone, rng: &mut Rando) -> S::Chromosone {
         let mut child = left.clone();
         let mut r = rng.uniform_iter(0..2);
-        for i in 0..left.len() {
+        for i in 0..left.as_ref().len() {
             if r.next() == Some(0) {
                 child[i] = right[i];
             }
@@ -31,12 +31,13 @@ impl<const N: usize, const NSYMS: usize> Crossover<N, NSYMS> for Mix<N, NSYMS> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chromosone::Fixed;
     use mockall::*;
 
     #[test]
     fn test_mix() {
         let mut r = Rando::default();
-        let m = Mix::<5, 3>::new();
+        let m: &dyn Crossover<Fixed<5, 3>> = &Mix::new();
         r.expect_uniform_iter()
             .with(predicate::eq(0..2))
             .times(1)
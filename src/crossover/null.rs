@@ -1,19 +1,19 @@
-use crate::chromosone::Gene;
+use crate::chromosone::Shape;
 use crate::crossover::Crossover;
 
 #[mockall_double::double]
 use crate::rando::Rando;
 
-pub struct Null<const N: usize, const NSYMS: usize> {}
+pub struct Null {}
 
-impl<const N: usize, const NSYMS: usize> Null<N, NSYMS> {
+impl Null {
     pub const fn new() -> Self {
         Self {}
     }
 }
 
-impl<const N: usize, const NSYMS: usize> Crossover<N, NSYMS> for Null<N, NSYMS> {
-    fn run(&self, left: &[Gene; N], _right: &[Gene; N], _rng: &mut Rando) -> [Gene; N] {
+impl<S: Shape> Crossover<S> for Null {
+    fn run(&self, left: &S::Chromosone, _right: &S::Chromosone, _rng: &mut Rando) -> S::Chromosone {
         left.clone()
     }
 }
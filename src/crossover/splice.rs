@@ -1,35 +1,36 @@
-use crate::chromosone::Gene;
+use crate::chromosone::Shape;
 use crate::crossover::Crossover;
 
 #[mockall_double::double]
 use crate::rando::Rando;
 
 /** If each chromosone is a deck of cards, splice would cut each deck twice in the same place, and then use the middle from one deck and the top & bottom from the other. **/
-pub struct Splice<const N: usize, const NSYMS: usize> {}
+pub struct Splice {}
 
-impl<const N: usize, const NSYMS: usize> Splice<N, NSYMS> {
+impl Splice {
     pub const fn new() -> Self {
         Self {}
     }
 }
 
-impl<const N: usize, const NSYMS: usize> Crossover<N, NSYMS> for Splice<N, NSYMS> {
-    fn run(&self, left: &[Gene; N], right: &[Gene; N], rng: &mut Rando) -> [Gene; N] {
+impl<S: Shape> Crossover<S> for Splice {
+    fn run(&self, left: &S::Chromosone, right: &S::Chromosone, rng: &mut Rando) -> S::Chromosone {
         let mut child = left.clone();
         let mut start;
         let mut end;
         loop {
-            start = rng.gen_range(0..child.len());
-            end = rng.gen_range(1..child.len() + 1);
+            start = rng.gen_range(0..left.as_ref().len());
+            end = rng.gen_range(1..left.as_ref().len() + 1);
             if start != end {
                 break;
             }
         }
+        let (child_genes, right) = (child.as_mut(), right.as_ref());
         if start > end {
-            child[..start].copy_from_slice(&right[..start]);
-            child[end..].copy_from_slice(&right[end..]);
+            child_genes[..start].copy_from_slice(&right[..start]);
+            child_genes[end..].copy_from_slice(&right[end..]);
         } else {
-            child[start..end].copy_from_slice(&right[start..end]);
+            child_genes[start..end].copy_from_slice(&right[start..end]);
         }
         child
     }
@@ -38,12 +39,13 @@ impl<const N: usize, const NSYMS: usize> Crossover<N, NSYMS> for Splice<N, NSYMS
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chromosone::Fixed;
     use mockall::*;
 
     #[test]
     fn test_splice() {
         let mut r = Rando::default();
-        let m = Splice::<5, 3>::new();
+        let m: &dyn Crossover<Fixed<5, 3>> = &Splice::new();
         r.expect_gen_range()
             .with(predicate::eq(0..5))
             .times(1)
@@ -0,0 +1,140 @@
+/*!
+
+A [Domain] lists the symbols each locus of a chromosone is allowed to take.
+
+Without one, [Candidate::new] and [Mutate] draw genes uniformly from `0..nsyms`, and a gene that can't go at a locus is only discovered as a constraint violation.   With one, [Candidate::new] only creates chromosones that fit the domain, a [Mutate] only changes a gene to a symbol allowed at its locus, and a [Rotate] only moves a gene to a locus that allows it.   The crossovers never move a gene to a different locus, so the child of two parents that fit the domain fits it too.   So the seeding phase no longer has to climb out of impossible assignments.
+
+Set [Gas::domain] and it is used for the initial populations and handed to every mutation through [Mutation::run_within].   [Gas::validate] checks that it has an entry for every locus and only symbols the shape has.   [InvalidPosition::domain] derives one from an [InvalidPosition] constraint.
+
+```
+# use gas::Gas;
+# use gas::chromosone::Fixed;
+# use gas::fitness::{self, FitnessConfig};
+# use gas::constraints::{ConstraintConfig, InvalidPosition};
+# use gas::candidate::Candidate;
+# use gas::gas::cycle::CycleProgress;
+# use gas::mutation::{self, MutationConfig};
+# use gas::rando::Rando;
+# use gas::stopping::*;
+# use std::sync::atomic::AtomicBool;
+# use std::sync::Arc;
+// gene i is worth 1.0 at locus i, so the best chromosone is [0, 1, 2, 3]
+let desirability = (0..6).map(|g| (0..4).map(|i| if g == i { 1.0 } else { 0.0 }).collect()).collect();
+// but gene 2 can't go at locus 2, and nothing but 4 and 5 can go at locus 3
+let invalid = InvalidPosition::new(vec![
+    vec![false, false, false, true],
+    vec![false, false, false, true],
+    vec![false, false, true, true],
+    vec![false, false, false, true],
+    vec![false, false, false, false],
+    vec![false, false, false, false],
+]);
+let domain = invalid.domain();
+let gas = Gas {
+  constraints: ConstraintConfig::new(vec![Box::new(invalid)]),
+  domain: Some(domain),
+  mutations: MutationConfig::new(vec![
+    (1, Box::new(mutation::Mutate::new(1, 6))),
+    (1, Box::new(mutation::Rotate::new(1))),
+  ]),
+#  stopping: StoppingConfig {
+#    stop: Box::new(MaxGenerations::new(100)),
+#    ..StoppingConfig::default()
+#  },
+  ..Gas::new(
+    Fixed::<4, 6>::new(),
+    FitnessConfig::new(vec![Box::new(fitness::LocusDesirability::new(desirability, 1.0))]),
+  )
+};
+let mut rng = Rando::seed_from_u64(1);
+assert!((0..100).all(|_| Candidate::new(&gas, &mut rng).violations == 0));
+
+let sigint = Arc::new(AtomicBool::new(false));
+let winner = gas.cycle(&mut CycleProgress::new(&gas, &sigint), Rando::seed_from_u64(1));
+assert_eq!(winner.violations, 0);
+assert_eq!(winner.chromosone[..2], [0, 1]);
+```
+
+ */
+
+use crate::chromosone::Shape;
+
+#[cfg(doc)]
+use crate::{
+    candidate::Candidate,
+    constraints::InvalidPosition,
+    gas::Gas,
+    mutation::{Mutate, Mutation, Rotate},
+};
+
+/// see module documentation
+#[derive(Clone, Debug, PartialEq)]
+pub struct Domain {
+    /// `allowed[locus]` are the symbols allowed at `locus`, in ascending order.   None may be empty.
+    pub allowed: Vec<Vec<usize>>,
+}
+
+impl Domain {
+    /// `allowed[locus]` are the symbols allowed at `locus`, in any order.   Panics if any locus allows no symbols at all.
+    pub fn new(mut allowed: Vec<Vec<usize>>) -> Domain {
+        for a in allowed.iter_mut() {
+            assert!(
+                !a.is_empty(),
+                "every locus needs at least one allowed symbol"
+            );
+            a.sort_unstable();
+            a.dedup();
+        }
+        Domain { allowed }
+    }
+
+    /// the symbols allowed at `locus`
+    pub fn symbols(&self, locus: usize) -> &[usize] {
+        &self.allowed[locus]
+    }
+
+    pub fn allows(&self, locus: usize, symbol: usize) -> bool {
+        self.allowed[locus].binary_search(&symbol).is_ok()
+    }
+
+    /// panics unless there is an entry for every locus of `shape`, and every symbol is one of its `nsyms`
+    pub fn check<S: Shape>(&self, shape: &S) {
+        assert_eq!(
+            self.allowed.len(),
+            shape.length(),
+            "the domain has a different number of loci to the shape"
+        );
+        assert!(
+            self.allowed.iter().flatten().all(|s| *s < shape.nsyms()),
+            "the domain allows a symbol the shape doesn't have"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chromosone::Dynamic;
+
+    #[test]
+    fn test_domain() {
+        let d = Domain::new(vec![vec![2, 0, 2], vec![1]]);
+        assert_eq!(d.symbols(0), [0, 2]);
+        assert!(d.allows(0, 2));
+        assert!(!d.allows(0, 1));
+        assert!(!d.allows(1, 0));
+        d.check(&Dynamic::new(2, 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "the domain has a different number of loci to the shape")]
+    fn test_domain_too_short() {
+        Domain::new(vec![vec![0], vec![1]]).check(&Dynamic::new(3, 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "the domain allows a symbol the shape doesn't have")]
+    fn test_domain_too_wide() {
+        Domain::new(vec![vec![0], vec![3]]).check(&Dynamic::new(2, 3));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::chromosone::Gene;
+use crate::chromosone::Shape;
 
 pub mod color_count;
 pub mod distance;
@@ -27,9 +27,19 @@ pub use weighted_count::WeightedCount;
 **  Implementations: [color_count::ColorCount], [distance::Distance], [weighted_count::WeightedCount]
 ***/
 
-pub trait FitnessFunction<const N: usize, const NSYMS: usize> {
+pub trait FitnessFunction<S: Shape> {
     /// returns a vector of floats where bigger numbers are better.   If your fitness function optimizes to 0, remember that 0 is the biggest negative number.  NaN is also a valid score, and means that the score cannot be compared and is considered a tie with any other number.
-    fn run(&self, chromosone: &[Gene; N]) -> Vec<f64>;
+    fn run(&self, chromosone: &S::Chromosone) -> Vec<f64>;
+    /// optional: the scores of `chromosone` calculated from the scores of `parent`.   The two chromosones differ only at the loci listed in `changed`, and `parent_scores` are the scores this function returned for `parent`.   Must return exactly what [FitnessFunction::run] would.  Implement this if the scores can be updated in less time than it takes to recalculate them; the default calls [FitnessFunction::run].
+    fn run_delta(
+        &self,
+        chromosone: &S::Chromosone,
+        _parent: &S::Chromosone,
+        _parent_scores: &[f64],
+        _changed: &[usize],
+    ) -> Vec<f64> {
+        self.run(chromosone)
+    }
     /// provides an [FitnessFunction.nscores] length human readable name for the scores returned.   Optional, but useful for debugging
     fn names(&self) -> Vec<FitnessName> {
         vec![]
@@ -39,6 +49,12 @@ pub trait FitnessFunction<const N: usize, const NSYMS: usize> {
     fn weights(&self) -> Vec<f64> {
         vec![1.0; self.nscores()]
     }
+    /// optional: how much this score must differ between two candidates before a game counts it as a win rather than a tie.   Useful for continuous scores, where a difference in the last few bits is floating point noise rather than a real improvement.   The vector must be [FitnessFunction.nscores] long.   The default is 0 for every score: any difference counts.
+    ///
+    /// **Tolerances are ignored unless the game is built from the [FitnessConfig]** with [Full::tolerant](crate::game::Full::tolerant), [Sample::tolerant](crate::game::Sample::tolerant), [Tiered::by_function](crate::game::Tiered::by_function) or [Tiered::by_prefix](crate::game::Tiered::by_prefix).   A [Gas](crate::Gas) doesn't pass them to its tournaments, so a game made with `new` counts every difference, however small.
+    fn tolerances(&self) -> Vec<f64> {
+        vec![0.0; self.nscores()]
+    }
 }
 
 /// FitnessName describes a score.   The full name would be [FitnessName.prefix] + [FitnessName.gene] name + [FitnessName.locus] name
@@ -52,21 +68,19 @@ pub struct FitnessName {
     pub locus: Option<usize>,
 }
 
-pub struct FitnessConfig<const N: usize, const NSYMS: usize> {
-    pub functions: Vec<Box<dyn FitnessFunction<N, NSYMS> + Sync + Send>>,
+pub struct FitnessConfig<S: Shape> {
+    pub functions: Vec<Box<dyn FitnessFunction<S> + Sync + Send>>,
     pub nscores: usize,
 }
 
-impl<const N: usize, const NSYMS: usize> FitnessConfig<N, NSYMS> {
-    pub fn new(
-        functions: Vec<Box<dyn FitnessFunction<N, NSYMS> + Sync + Send>>,
-    ) -> FitnessConfig<N, NSYMS> {
+impl<S: Shape> FitnessConfig<S> {
+    pub fn new(functions: Vec<Box<dyn FitnessFunction<S> + Sync + Send>>) -> FitnessConfig<S> {
         let nscores = FitnessConfig::nscores(&functions);
         FitnessConfig { functions, nscores }
     }
 
     /// helper function, use [FitnessConfig::new]
-    fn nscores(functions: &Vec<Box<dyn FitnessFunction<N, NSYMS> + Sync + Send>>) -> usize {
+    fn nscores(functions: &Vec<Box<dyn FitnessFunction<S> + Sync + Send>>) -> usize {
         // this used to be a const fn, that's why we don't use for or iter.
         let mut sum = 0usize;
         let mut i = 0usize;
@@ -77,7 +91,7 @@ impl<const N: usize, const NSYMS: usize> FitnessConfig<N, NSYMS> {
         sum
     }
 
-    pub fn scores(&self, chromosone: &[Gene; N]) -> Vec<f64> {
+    pub fn scores(&self, chromosone: &S::Chromosone) -> Vec<f64> {
         let mut scores = Vec::<f64>::with_capacity(self.nscores);
         for func in self.functions.iter() {
             scores.append(&mut func.run(chromosone));
@@ -85,6 +99,29 @@ impl<const N: usize, const NSYMS: usize> FitnessConfig<N, NSYMS> {
         scores
     }
 
+    /// like [FitnessConfig::scores], but using [FitnessFunction::run_delta] with the scores of a parent that differs only at the `changed` loci.
+    pub fn scores_delta(
+        &self,
+        chromosone: &S::Chromosone,
+        parent: &S::Chromosone,
+        parent_scores: &[f64],
+        changed: &[usize],
+    ) -> Vec<f64> {
+        let mut scores = Vec::<f64>::with_capacity(self.nscores);
+        let mut offset = 0usize;
+        for func in self.functions.iter() {
+            let n = func.nscores();
+            scores.append(&mut func.run_delta(
+                chromosone,
+                parent,
+                &parent_scores[offset..offset + n],
+                changed,
+            ));
+            offset += n;
+        }
+        scores
+    }
+
     pub fn weights(&self) -> Vec<f64> {
         self.functions.iter().fold(
             Vec::<f64>::with_capacity(self.nscores),
@@ -95,6 +132,17 @@ impl<const N: usize, const NSYMS: usize> FitnessConfig<N, NSYMS> {
         )
     }
 
+    /// the [tolerances](FitnessFunction::tolerances) of every score, for the constructors of the games that use them
+    pub fn tolerances(&self) -> Vec<f64> {
+        self.functions.iter().fold(
+            Vec::<f64>::with_capacity(self.nscores),
+            |mut tolerances, func| {
+                tolerances.extend_from_slice(&func.tolerances());
+                tolerances
+            },
+        )
+    }
+
     pub fn names(&self) -> Vec<FitnessName> {
         self.functions.iter().fold(
             Vec::<FitnessName>::with_capacity(self.nscores),
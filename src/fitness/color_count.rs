@@ -1,4 +1,4 @@
-use crate::chromosone::Gene;
+use crate::chromosone::{GeneType, Shape};
 
 use super::{FitnessFunction, FitnessName};
 
@@ -14,15 +14,17 @@ Parameters:
 
 */
 
-pub struct ColorCount<const N: usize, const NSYMS: usize> {
+pub struct ColorCount {
     pub ncolors: usize,
     pub chromosone_colors: Vec<usize>,
     pub preferences: Vec<Vec<usize>>,
     pub color_names: &'static [&'static str],
     pub weight: f64,
+    /// the loci of each color, derived from `chromosone_colors`.   Used by [FitnessFunction::run_delta].
+    pub color_loci: Vec<Vec<usize>>,
 }
 
-impl<const N: usize, const NSYMS: usize> ColorCount<N, NSYMS> {
+impl ColorCount {
     /// see [`ColorCount`] docs
     pub fn new(
         ncolors: usize,
@@ -34,36 +36,43 @@ impl<const N: usize, const NSYMS: usize> ColorCount<N, NSYMS> {
         for prefs in preferences.iter() {
             assert_eq!(ncolors, prefs.len());
         }
+        let mut color_loci = vec![vec![]; ncolors];
+        for (i, color) in chromosone_colors.iter().enumerate() {
+            color_loci[*color].push(i);
+        }
         ColorCount {
             ncolors,
             chromosone_colors,
             preferences,
             color_names,
             weight,
+            color_loci,
         }
     }
 }
 
-impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for ColorCount<N, NSYMS> {
+impl<S: Shape> FitnessFunction<S> for ColorCount {
     fn nscores(&self) -> usize {
-        self.ncolors * NSYMS
+        self.ncolors * self.preferences.len()
     }
 
     fn weights(&self) -> Vec<f64> {
-        vec![self.weight; self.nscores()]
+        vec![self.weight; FitnessFunction::<S>::nscores(self)]
     }
 
-    fn run(&self, chromosone: &[Gene; N]) -> Vec<f64> {
+    fn run(&self, chromosone: &S::Chromosone) -> Vec<f64> {
+        let chromosone = chromosone.as_ref();
+        let nsyms = self.preferences.len();
         assert_eq!(self.chromosone_colors.len(), chromosone.len());
-        let mut scores = Vec::<f64>::with_capacity(self.nscores());
-        let mut counts: Vec<Vec<usize>> = vec![vec![0; self.ncolors]; NSYMS];
+        let mut scores = Vec::<f64>::with_capacity(self.ncolors * nsyms);
+        let mut counts: Vec<Vec<usize>> = vec![vec![0; self.ncolors]; nsyms];
 
         for (i, sym) in chromosone.iter().enumerate() {
             let color = self.chromosone_colors[i];
-            counts[*sym as usize][color] += 1;
+            counts[sym.to_usize()][color] += 1;
         }
 
-        for m in 0..NSYMS {
+        for m in 0..nsyms {
             for n in 0..self.ncolors {
                 scores.push(-(counts[m][n].abs_diff(self.preferences[m][n]) as f64))
             }
@@ -72,9 +81,42 @@ impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for ColorCoun
         scores
     }
 
+    /// A score doesn't tell us whether the count is above or below the preference, so the symbol/color pairs touched by the change are recounted over the loci of that color.   Each recount covers roughly `N / ncolors` loci, so when more than `ncolors / 2` loci have changed it is cheaper to start from scratch.
+    fn run_delta(
+        &self,
+        chromosone: &S::Chromosone,
+        parent: &S::Chromosone,
+        parent_scores: &[f64],
+        changed: &[usize],
+    ) -> Vec<f64> {
+        if changed.len() * 2 > self.ncolors {
+            return FitnessFunction::<S>::run(self, chromosone);
+        }
+        let mut scores = parent_scores.to_vec();
+        let mut pairs = Vec::<(usize, usize)>::with_capacity(changed.len() * 2);
+        for &i in changed {
+            let color = self.chromosone_colors[i];
+            for sym in [parent[i].to_usize(), chromosone[i].to_usize()] {
+                if !pairs.contains(&(sym, color)) {
+                    pairs.push((sym, color));
+                }
+            }
+        }
+        for (sym, color) in pairs {
+            let count = self.color_loci[color]
+                .iter()
+                .filter(|i| chromosone[**i].to_usize() == sym)
+                .count();
+            scores[sym * self.ncolors + color] =
+                -(count.abs_diff(self.preferences[sym][color]) as f64);
+        }
+        scores
+    }
+
     fn names(&self) -> Vec<FitnessName> {
-        let mut names = Vec::<FitnessName>::with_capacity(self.nscores());
-        for m in 0..NSYMS {
+        let nsyms = self.preferences.len();
+        let mut names = Vec::<FitnessName>::with_capacity(self.ncolors * nsyms);
+        for m in 0..nsyms {
             for n in 0..self.ncolors {
                 names.push(FitnessName {
                     prefix: format!("{} {}", self.color_names[n], self.preferences[m][n]),
@@ -112,10 +154,11 @@ impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for ColorCoun
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chromosone::Fixed;
 
     #[test]
     fn test_color_count() {
-        let cc = ColorCount::<5, 3>::new(
+        let cc: &dyn FitnessFunction<Fixed<5, 3>> = &ColorCount::new(
             2,
             vec![0, 1, 0, 1, 0],
             vec![vec![1, 1], vec![0, 0], vec![2, 2]],
@@ -131,5 +174,9 @@ mod tests {
             vec![/*sym0*/ -1.0, 0.0, /*sym1*/ -1.0, -1.0, /*sym2*/ -2.0, -2.0]
         );
         assert_eq!(cc.nscores(), scores.len());
+        assert_eq!(
+            cc.run_delta(&[0, 2, 0, 1, 1], &[0, 0, 0, 1, 1], &scores, &[1]),
+            cc.run(&[0, 2, 0, 1, 1])
+        );
     }
 }
@@ -1,7 +1,5 @@
 use super::{FitnessFunction, FitnessName};
-use crate::chromosone::Gene;
-
-use array_init::array_init;
+use crate::chromosone::{GeneType, Shape};
 
 /**
 The Distance fitness scores discourage clumping of symbols in the chromosone and encourage identical symbols to spread out evenly.
@@ -16,23 +14,24 @@ The parameters `distance_before` and `distance_after` are useful when the chromo
 
 If the symbol does not occur at least twice in the chromosone (along with distance_before and distance_after) resulting in a distance that cannot be calculated, then the score is NaN.   Tournaments consider any comparison with NaN to be a tie so they are indifferent between any spacing and no spacing.
 */
-pub struct Distance<const N: usize, const NSYMS: usize> {
+pub struct Distance {
     pub max: usize,
-    pub distance_before: [Option<usize>; NSYMS],
-    pub distance_after: [Option<usize>; NSYMS],
+    pub distance_before: Vec<Option<usize>>,
+    pub distance_after: Vec<Option<usize>>,
     pub weight_minimum: f64,
     pub weight_stdev: f64,
 }
 
-impl<const N: usize, const NSYMS: usize> Distance<N, NSYMS> {
+impl Distance {
     /// creates a new [`Distance`].  `max` constrains resulting scores.  For instance in a scheduling system if you consider that as long as shifts are at least a week apart further spacing is not an improvement, you could set `max` to 7 (assuming there's only one shift per day in the chromoone).   The weights are used in the [`FitnessFunction.weights`] function -- 1.0 is a reasonable value for both.
-    pub const fn new(
+    pub fn new(
         max: usize,
-        distance_before: [Option<usize>; NSYMS], // length  chromosone::MAX,
-        distance_after: [Option<usize>; NSYMS],  // length chromosone::MAX,
+        distance_before: Vec<Option<usize>>, // length NSYMS
+        distance_after: Vec<Option<usize>>,  // length NSYMS
         weight_minimum: f64,
         weight_stdev: f64,
-    ) -> Distance<N, NSYMS> {
+    ) -> Distance {
+        assert_eq!(distance_before.len(), distance_after.len());
         Distance {
             max,
             distance_before,
@@ -42,12 +41,18 @@ impl<const N: usize, const NSYMS: usize> Distance<N, NSYMS> {
         }
     }
 
-    fn distances(&self, chromosone: &[Gene; N]) -> [Vec<usize>; NSYMS] {
-        let mut current_position: [Option<usize>; NSYMS] = [None; NSYMS];
-        let mut distances: [Vec<usize>; NSYMS] = array_init(|_| vec![]);
+    /// the number of symbols, from the length of `distance_before`
+    fn nsyms(&self) -> usize {
+        self.distance_before.len()
+    }
+
+    fn distances(&self, chromosone: &[impl GeneType]) -> Vec<Vec<usize>> {
+        let n = chromosone.len();
+        let mut current_position: Vec<Option<usize>> = vec![None; self.nsyms()];
+        let mut distances: Vec<Vec<usize>> = vec![vec![]; self.nsyms()];
 
-        for pos in 0..N {
-            let g: usize = chromosone[pos].into();
+        for pos in 0..n {
+            let g = chromosone[pos].to_usize();
             if let Some(curpos) = current_position[g] {
                 distances[g].push(pos - curpos);
             } else {
@@ -58,13 +63,13 @@ impl<const N: usize, const NSYMS: usize> Distance<N, NSYMS> {
             current_position[g] = Some(pos);
         }
 
-        for g in 0..NSYMS {
+        for g in 0..self.nsyms() {
             if let Some(after) = self.distance_after[g] {
                 if let Some(curpos) = current_position[g] {
-                    distances[g].push(after + (N - curpos - 1));
+                    distances[g].push(after + (n - curpos - 1));
                 } else {
                     if let Some(before) = self.distance_before[g] {
-                        distances[g].push(after + (N - 1) + before);
+                        distances[g].push(after + (n - 1) + before);
                     }
                 }
             }
@@ -73,24 +78,34 @@ impl<const N: usize, const NSYMS: usize> Distance<N, NSYMS> {
     }
 }
 
-impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for Distance<N, NSYMS> {
+impl<S: Shape> FitnessFunction<S> for Distance {
     fn nscores(&self) -> usize {
-        2 * NSYMS
+        2 * self.nsyms()
     }
 
     fn weights(&self) -> Vec<f64> {
-        let mut weights = Vec::<f64>::with_capacity(self.nscores());
-        for _ in 0..NSYMS {
+        let mut weights = Vec::<f64>::with_capacity(FitnessFunction::<S>::nscores(self));
+        for _ in 0..self.nsyms() {
             weights.push(self.weight_minimum);
             weights.push(self.weight_stdev);
         }
         weights
     }
 
-    fn run(&self, chromosone: &[Gene; N]) -> Vec<f64> {
-        let mut scores: Vec<f64> = Vec::with_capacity(NSYMS * 2);
-        let distances = self.distances(chromosone);
-        for g in 0..NSYMS {
+    /// the standard deviations are calculated in floating point, so tiny differences are noise.   The minimums are exact.
+    fn tolerances(&self) -> Vec<f64> {
+        let mut tolerances = Vec::<f64>::with_capacity(FitnessFunction::<S>::nscores(self));
+        for _ in 0..self.nsyms() {
+            tolerances.push(0.0);
+            tolerances.push(1e-9);
+        }
+        tolerances
+    }
+
+    fn run(&self, chromosone: &S::Chromosone) -> Vec<f64> {
+        let mut scores: Vec<f64> = Vec::with_capacity(self.nsyms() * 2);
+        let distances = self.distances(chromosone.as_ref());
+        for g in 0..self.nsyms() {
             if distances[g].len() > 0 {
                 let minimum = distances[g].iter().min().unwrap();
                 let min_count = distances[g].iter().fold(0usize, |count, d| {
@@ -127,8 +142,8 @@ impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for Distance<
     }
 
     fn names(&self) -> Vec<FitnessName> {
-        let mut names = Vec::<FitnessName>::with_capacity(self.nscores());
-        for g in 0..NSYMS {
+        let mut names = Vec::<FitnessName>::with_capacity(FitnessFunction::<S>::nscores(self));
+        for g in 0..self.nsyms() {
             names.push(FitnessName {
                 prefix: "min distance".to_string(),
                 gene: Some(g),
@@ -149,10 +164,12 @@ mod tests {
     use crate::fitness::assert_scores_eq;
 
     use super::*;
+    use crate::chromosone::Fixed;
 
     #[test]
     fn test_distance() {
-        let d = Distance::new(7, [None; 3], [None; 3], 1.0, 1.0);
+        let d: &dyn FitnessFunction<Fixed<5, 3>> =
+            &Distance::new(7, vec![None; 3], vec![None; 3], 1.0, 1.0);
         let scores = d.run(&[0, 0, 1, 0, 1]);
         assert_scores_eq(
             &scores,
@@ -169,7 +186,8 @@ mod tests {
     }
     #[test]
     fn test_max() {
-        let d = Distance::new(1, [None; 3], [None; 3], 1.0, 1.0);
+        let d: &dyn FitnessFunction<Fixed<5, 3>> =
+            &Distance::new(1, vec![None; 3], vec![None; 3], 1.0, 1.0);
         assert_scores_eq(
             &d.run(&[0, 0, 1, 0, 1]),
             &[
@@ -184,10 +202,10 @@ mod tests {
     }
     #[test]
     fn test_before_after() {
-        let d = Distance::new(
+        let d: &dyn FitnessFunction<Fixed<5, 3>> = &Distance::new(
             99,
-            [Some(1), Some(2), Some(9)],
-            [Some(1), Some(3), Some(9)],
+            vec![Some(1), Some(2), Some(9)],
+            vec![Some(1), Some(3), Some(9)],
             1.0,
             1.0,
         );
@@ -1,18 +1,18 @@
 use super::{FitnessFunction, FitnessName};
-use crate::chromosone::Gene;
+use crate::chromosone::{GeneType, Shape};
 
 /**
 
 If each symbol has preferred locations in the chromosone, this may be expressed using the `LocusDesirability` fitness score.   The input to this FitnessFunction is the score each symbol gives to each locus in the chromosone.   The fitness function simply reports the chosen symbol's score for each locus in the genome.
 
 */
-pub struct LocusDesirability<const N: usize, const NSYMS: usize> {
+pub struct LocusDesirability {
     pub symbol_scores: Vec<Vec<f64>>, // chromosone::LENGTH; chromosone::NSYMS
     pub weight: f64,
 }
 
-impl<const N: usize, const NSYMS: usize> LocusDesirability<N, NSYMS> {
-    pub const fn new(symbol_scores: Vec<Vec<f64>>, weight: f64) -> LocusDesirability<N, NSYMS> {
+impl LocusDesirability {
+    pub const fn new(symbol_scores: Vec<Vec<f64>>, weight: f64) -> LocusDesirability {
         LocusDesirability {
             symbol_scores,
             weight,
@@ -20,25 +20,40 @@ impl<const N: usize, const NSYMS: usize> LocusDesirability<N, NSYMS> {
     }
 }
 
-impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for LocusDesirability<N, NSYMS> {
+impl<S: Shape> FitnessFunction<S> for LocusDesirability {
     fn nscores(&self) -> usize {
-        N
+        self.symbol_scores[0].len()
     }
 
     fn weights(&self) -> Vec<f64> {
-        return vec![self.weight; self.nscores()];
+        return vec![self.weight; FitnessFunction::<S>::nscores(self)];
     }
 
-    fn run(&self, chromosone: &[Gene; N]) -> Vec<f64> {
+    fn run(&self, chromosone: &S::Chromosone) -> Vec<f64> {
         chromosone
+            .as_ref()
             .iter()
             .enumerate()
-            .map(|(i, gene)| self.symbol_scores[*gene as usize][i])
+            .map(|(i, gene)| self.symbol_scores[gene.to_usize()][i])
             .collect()
     }
 
+    fn run_delta(
+        &self,
+        chromosone: &S::Chromosone,
+        _parent: &S::Chromosone,
+        parent_scores: &[f64],
+        changed: &[usize],
+    ) -> Vec<f64> {
+        let mut scores = parent_scores.to_vec();
+        for &i in changed {
+            scores[i] = self.symbol_scores[chromosone[i].to_usize()][i];
+        }
+        scores
+    }
+
     fn names(&self) -> Vec<FitnessName> {
-        (0..N)
+        (0..self.symbol_scores[0].len())
             .map(|l| FitnessName {
                 prefix: "".to_string(),
                 gene: None,
@@ -53,7 +68,7 @@ impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for LocusDesi
             let mut count = 0f64;
             let mut sum = 0f64;
             for i in 0..chromosone.len() {
-                if chromosone[i] as usize == g {
+                if chromosone[i].to_usize() == g {
                     count += 1.0;
                     sum += self.symbol_scores[g][i];
                 }
@@ -68,10 +83,11 @@ impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for LocusDesi
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chromosone::Fixed;
 
     #[test]
     fn test_locus_desirability() {
-        let wc = LocusDesirability::<5, 3>::new(
+        let wc: &dyn FitnessFunction<Fixed<5, 3>> = &LocusDesirability::new(
             vec![
                 vec![2.0, 2.0, 1.0, 1.0, 0.0],
                 vec![1.0, 1.0, 2.0, 2.0, 0.0],
@@ -82,5 +98,9 @@ mod tests {
         let scores = wc.run(&[0, 0, 0, 1, 1]);
         assert_eq!(scores, vec![2.0, 2.0, 1.0, 2.0, 0.0]);
         assert_eq!(wc.nscores(), scores.len());
+        assert_eq!(
+            wc.run_delta(&[0, 2, 0, 1, 2], &[0, 0, 0, 1, 1], &scores, &[1, 4]),
+            wc.run(&[0, 2, 0, 1, 2])
+        );
     }
 }
@@ -1,19 +1,19 @@
 use super::{FitnessFunction, FitnessName};
-use crate::chromosone::Gene;
+use crate::chromosone::{GeneType, Shape};
 
 /**
 
 If each symbol has preferred locations in the chromosone, this may be expressed using the `WeightedCount` fitness score.   Every symbol has a weight for each position in the chromosone.   The weight is a very small positive integer indicating the desirability of the position.  The higher the weight, the more scores that increase when a symbol is placed in that position.
 
 */
-pub struct WeightedCount<const N: usize, const NSYMS: usize> {
+pub struct WeightedCount {
     pub max_weight: usize,
     pub weights: Vec<Vec<usize>>, //LENGTH; chromosone::NSYMS
 }
 
-impl<const N: usize, const NSYMS: usize> WeightedCount<N, NSYMS> {
+impl WeightedCount {
     /// see [`WeightedCount`].   `max_weight` is the maximum value for a weight.   [`WeightedCount`] will return `max_weight` scores for each symbol, so you likely want to set `max_weight` to a small number, like 1 or 3.
-    pub const fn new(max_weight: usize, weights: Vec<Vec<usize>>) -> WeightedCount<N, NSYMS> {
+    pub const fn new(max_weight: usize, weights: Vec<Vec<usize>>) -> WeightedCount {
         WeightedCount {
             max_weight,
             weights,
@@ -21,21 +21,42 @@ impl<const N: usize, const NSYMS: usize> WeightedCount<N, NSYMS> {
     }
 }
 
-impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for WeightedCount<N, NSYMS> {
+impl<S: Shape> FitnessFunction<S> for WeightedCount {
     fn nscores(&self) -> usize {
-        self.max_weight * NSYMS
+        self.max_weight * self.weights.len()
     }
 
     fn weights(&self) -> Vec<f64> {
-        return vec![1.0; self.nscores()];
+        return vec![1.0; FitnessFunction::<S>::nscores(self)];
     }
 
-    fn run(&self, chromosone: &[Gene; N]) -> Vec<f64> {
-        let mut scores: Vec<f64> = vec![0f64; self.nscores()];
+    fn run(&self, chromosone: &S::Chromosone) -> Vec<f64> {
+        let mut scores: Vec<f64> = vec![0f64; FitnessFunction::<S>::nscores(self)];
 
-        for i in 0..chromosone.len() {
-            for w in 0..self.weights[chromosone[i] as usize][i] {
-                scores[chromosone[i] as usize * self.max_weight + w as usize] += 1.0;
+        for i in 0..chromosone.as_ref().len() {
+            for w in 0..self.weights[chromosone[i].to_usize()][i] {
+                scores[chromosone[i].to_usize() * self.max_weight + w as usize] += 1.0;
+            }
+        }
+
+        scores
+    }
+
+    fn run_delta(
+        &self,
+        chromosone: &S::Chromosone,
+        parent: &S::Chromosone,
+        parent_scores: &[f64],
+        changed: &[usize],
+    ) -> Vec<f64> {
+        let mut scores = parent_scores.to_vec();
+
+        for &i in changed {
+            for w in 0..self.weights[parent[i].to_usize()][i] {
+                scores[parent[i].to_usize() * self.max_weight + w] -= 1.0;
+            }
+            for w in 0..self.weights[chromosone[i].to_usize()][i] {
+                scores[chromosone[i].to_usize() * self.max_weight + w] += 1.0;
             }
         }
 
@@ -43,8 +64,8 @@ impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for WeightedC
     }
 
     fn names(&self) -> Vec<FitnessName> {
-        let mut names: Vec<FitnessName> = Vec::with_capacity(self.nscores());
-        for i in 0..NSYMS {
+        let mut names: Vec<FitnessName> = Vec::with_capacity(FitnessFunction::<S>::nscores(self));
+        for i in 0..self.weights.len() {
             for w in 0..self.max_weight {
                 names.push(FitnessName {
                     prefix: format!("desirability{w}"),
@@ -59,10 +80,11 @@ impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for WeightedC
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chromosone::Fixed;
 
     #[test]
     fn test_weighted() {
-        let wc = WeightedCount::<5, 3>::new(
+        let wc: &dyn FitnessFunction<Fixed<5, 3>> = &WeightedCount::new(
             2,
             vec![
                 vec![2, 2, 1, 1, 0],
@@ -73,5 +95,9 @@ mod tests {
         let scores = wc.run(&[0, 0, 0, 1, 1]);
         assert_eq!(scores, vec![3.0, 2.0, 1.0, 1.0, 0.0, 0.0]);
         assert_eq!(wc.nscores(), scores.len());
+        assert_eq!(
+            wc.run_delta(&[0, 2, 0, 1, 2], &[0, 0, 0, 1, 1], &scores, &[1, 4]),
+            wc.run(&[0, 2, 0, 1, 2])
+        );
     }
 }
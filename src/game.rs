@@ -1,10 +1,13 @@
 pub mod full;
 pub mod sample;
+pub mod tiered;
 
 pub use full::Full;
 pub use sample::Sample;
+pub use tiered::Tiered;
 
 use crate::candidate::Candidate;
+use crate::chromosone::Shape;
 
 #[mockall_double::double]
 use crate::rando::Rando;
@@ -15,14 +18,46 @@ pub enum LeftRight {
     Right,
 }
 
-pub trait Game<const N: usize, const NSYMS: usize> {
+pub trait Game<S: Shape> {
     /// not all games require an RNG, but most do, and putting the RNG into the constructor means we'd need to use mutable Game's.
     /// similarly, not all games use [Game.score_weights], but some do and all probably should.
     fn run(
         &self,
-        left: &Candidate<N, NSYMS>,
-        right: &Candidate<N, NSYMS>,
+        left: &Candidate<S>,
+        right: &Candidate<S>,
         rng: &mut Rando,
         score_weights: &Vec<f64>,
     ) -> LeftRight;
 }
+
+/// which of two scores is better by more than `tolerance`, or `None` if they are tied.   Any comparison with NaN is a tie.   Games use this to compare each score, with the [tolerances](crate::fitness::FitnessFunction::tolerances) the fitness functions declare.
+pub fn better(left: f64, right: f64, tolerance: f64) -> Option<LeftRight> {
+    if left - right > tolerance {
+        Some(LeftRight::Left)
+    } else if right - left > tolerance {
+        Some(LeftRight::Right)
+    } else {
+        None
+    }
+}
+
+/// the tolerance of score `i`, 0 if `tolerances` doesn't cover it
+fn tolerance(tolerances: &[f64], i: usize) -> f64 {
+    tolerances.get(i).copied().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_better() {
+        assert_eq!(better(1.0, 0.0, 0.0), Some(LeftRight::Left));
+        assert_eq!(better(0.0, 1.0, 0.0), Some(LeftRight::Right));
+        assert_eq!(better(1.0, 1.0, 0.0), None);
+        assert_eq!(better(0.1 + 0.2, 0.3, 0.0), Some(LeftRight::Left));
+        assert_eq!(better(0.1 + 0.2, 0.3, 1e-9), None);
+        assert_eq!(better(f64::NAN, 0.0, 0.0), None);
+        assert_eq!(better(f64::INFINITY, 0.0, 1e-9), Some(LeftRight::Left));
+    }
+}
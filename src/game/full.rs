@@ -1,27 +1,42 @@
 use crate::candidate::Candidate;
-use crate::game::{Game, LeftRight};
+use crate::chromosone::Shape;
+use crate::fitness::FitnessConfig;
+use crate::game::{better, tolerance, Game, LeftRight};
 
 #[mockall_double::double]
 use crate::rando::Rando;
 
-#[derive(Debug, Clone, Copy)]
-pub struct Full<const N: usize, const NSYMS: usize> {}
+#[derive(Debug, Clone)]
+pub struct Full {
+    /// how much each score must differ by to count.   Empty counts any difference.
+    pub tolerances: Vec<f64>,
+}
 
 /// A game that compares every score at the same position between candidate, all
 /// metrics with equal weighting. The candidate with the least violations wins.
 /// If that is equal, the candidate that is superior in the most scores wins. In
 /// the case of a tie, winner is random.
-impl<const N: usize, const NSYMS: usize> Full<N, NSYMS> {
-    pub const fn new() -> Full<N, NSYMS> {
-        Full {}
+impl Full {
+    /// counts any difference in a score as a win, ignoring the [tolerances](crate::fitness::FitnessFunction::tolerances).   See [Full::tolerant].
+    pub const fn new() -> Full {
+        Full {
+            tolerances: Vec::new(),
+        }
+    }
+
+    /// scores that differ by no more than the [tolerances](crate::fitness::FitnessFunction::tolerances) of `fitness` are tied
+    pub fn tolerant<S: Shape>(fitness: &FitnessConfig<S>) -> Full {
+        Full {
+            tolerances: fitness.tolerances(),
+        }
     }
 }
 
-impl<const N: usize, const NSYMS: usize> Game<N, NSYMS> for Full<N, NSYMS> {
+impl<S: Shape> Game<S> for Full {
     fn run(
         &self,
-        left: &Candidate<N, NSYMS>,
-        right: &Candidate<N, NSYMS>,
+        left: &Candidate<S>,
+        right: &Candidate<S>,
         rng: &mut Rando,
         score_weights: &Vec<f64>,
     ) -> LeftRight {
@@ -37,12 +52,10 @@ impl<const N: usize, const NSYMS: usize> Game<N, NSYMS> for Full<N, NSYMS> {
             .zip(right.scores.iter())
             .enumerate()
             .fold((0.0, 0.0), |pts, (i, scores)| {
-                if scores.0 > scores.1 {
-                    (pts.0 + score_weights[i], pts.1)
-                } else if scores.1 > scores.0 {
-                    (pts.0, pts.1 + score_weights[i])
-                } else {
-                    pts
+                match better(*scores.0, *scores.1, tolerance(&self.tolerances, i)) {
+                    Some(LeftRight::Left) => (pts.0 + score_weights[i], pts.1),
+                    Some(LeftRight::Right) => (pts.0, pts.1 + score_weights[i]),
+                    None => pts,
                 }
             });
 
@@ -66,11 +79,12 @@ use mockall::*;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chromosone::Fixed;
 
     #[test]
     fn test_game() {
         let mut r = Rando::default();
-        let g = Full::<5, 3>::new();
+        let g: &dyn Game<Fixed<5, 3>> = &Full::new();
         assert_eq!(
             LeftRight::Left,
             g.run(
@@ -93,7 +107,7 @@ mod tests {
     #[test]
     fn test_game_weights() {
         let mut r = Rando::default();
-        let g = Full::<5, 3>::new();
+        let g: &dyn Game<Fixed<5, 3>> = &Full::new();
         assert_eq!(
             LeftRight::Right,
             g.run(
@@ -116,7 +130,7 @@ mod tests {
     #[test]
     fn test_game_violations() {
         let mut r = Rando::default();
-        let g = Full::<5, 3>::new();
+        let g: &dyn Game<Fixed<5, 3>> = &Full::new();
         assert_eq!(
             LeftRight::Right,
             g.run(
@@ -143,7 +157,7 @@ mod tests {
             .with(predicate::eq(0..2))
             .times(1)
             .return_const(1usize);
-        let g = Full::<5, 3>::new();
+        let g: &dyn Game<Fixed<5, 3>> = &Full::new();
         assert_eq!(
             LeftRight::Right,
             g.run(
@@ -162,4 +176,33 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_game_tolerant() {
+        let gas = crate::gas::Gas::dut();
+        let left = Candidate {
+            chromosone: [0, 0, 0, 0, 0],
+            scores: vec![0.0, 0.1 + 0.2, 0.0, 0.0, 0.0, 0.0],
+            violations: 0,
+        };
+        let right = Candidate {
+            chromosone: [0, 0, 0, 0, 0],
+            scores: vec![0.0, 0.3, 0.0, 0.0, 0.0, 0.0],
+            violations: 0,
+        };
+        let mut r = Rando::default();
+        let g: &dyn Game<Fixed<5, 3>> = &Full::new();
+        assert_eq!(LeftRight::Left, g.run(&left, &right, &mut r, &vec![1.0; 6]));
+
+        // the Distance stdev scores are within tolerance, so it's a tie
+        r.expect_gen_range()
+            .with(predicate::eq(0..2))
+            .times(1)
+            .return_const(1usize);
+        let g: &dyn Game<Fixed<5, 3>> = &Full::tolerant(&gas.fitness);
+        assert_eq!(
+            LeftRight::Right,
+            g.run(&left, &right, &mut r, &vec![1.0; 6])
+        );
+    }
 }
@@ -2,14 +2,24 @@
 use mockall::*;
 
 use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+use crate::fitness::FitnessConfig;
 use crate::game::*;
+use std::sync::{Arc, RwLock};
 
 #[mockall_double::double]
 use crate::rando::Rando;
 
-#[derive(Debug, Clone)]
-pub struct Sample<const N: usize, const NSYMS: usize> {
+/// some score weights and their running total
+type WeightsTotal = (Vec<f64>, Arc<Vec<f64>>);
+
+#[derive(Debug)]
+pub struct Sample {
     pub tries_per_game: std::ops::Range<usize>,
+    /// how much each score must differ by to win the point.   Empty counts any difference.
+    pub tolerances: Vec<f64>,
+    /// the score weights of the last game and their running total, so a score position can be drawn in proportion to its weight without adding them up every game
+    cumulative: RwLock<Option<WeightsTotal>>,
 }
 
 /// a single game between two candidates Each game consists of a number of point
@@ -18,17 +28,94 @@ pub struct Sample<const N: usize, const NSYMS: usize> {
 /// point. Whoever has the most points wins. In the case of a tie, up to 10 tie
 /// breakers are attempted. If it is still tied after 10 tie-breakers, the point
 /// goes to left.
-impl<const N: usize, const NSYMS: usize> Sample<N, NSYMS> {
+///
+/// Score positions are selected in proportion to `score_weights`, so a score
+/// with weight 5 is tried five times as often as a score with weight 1.   A
+/// score with weight 0 is never tried.   If the weights are all the same every
+/// position is equally likely.
+impl Sample {
+    /// counts any difference in a score as a win, ignoring the [tolerances](crate::fitness::FitnessFunction::tolerances).   See [Sample::tolerant].
     pub const fn new(tries_per_game: std::ops::Range<usize>) -> Self {
-        Sample { tries_per_game }
+        Sample {
+            tries_per_game,
+            tolerances: Vec::new(),
+            cumulative: RwLock::new(None),
+        }
+    }
+
+    /// the same as [Sample::new], but the running total of the [weights](crate::fitness::FitnessFunction::weights) of `fitness`, which are the `score_weights` a [Gas](crate::Gas) plays its games with, is worked out up front rather than in the first game
+    pub fn weighted<S: Shape>(
+        tries_per_game: std::ops::Range<usize>,
+        fitness: &FitnessConfig<S>,
+    ) -> Self {
+        let weights = fitness.weights();
+        let cumulative = Arc::new(Sample::running_total(&weights));
+        Sample {
+            cumulative: RwLock::new(Some((weights, cumulative))),
+            ..Sample::new(tries_per_game)
+        }
+    }
+
+    /// scores that differ by no more than the [tolerances](crate::fitness::FitnessFunction::tolerances) of `fitness` are tied
+    pub fn tolerant<S: Shape>(
+        tries_per_game: std::ops::Range<usize>,
+        fitness: &FitnessConfig<S>,
+    ) -> Self {
+        Sample {
+            tolerances: fitness.tolerances(),
+            ..Sample::weighted(tries_per_game, fitness)
+        }
+    }
+
+    /// the running total of `score_weights`, reused from the last game if its weights were the same
+    fn cumulative(&self, score_weights: &[f64]) -> Arc<Vec<f64>> {
+        {
+            if let Some((weights, cumulative)) = &*self.cumulative.read().unwrap() {
+                if weights == score_weights {
+                    return cumulative.clone();
+                }
+            }
+        }
+        let cumulative = Arc::new(Sample::running_total(score_weights));
+        *self.cumulative.write().unwrap() = Some((score_weights.to_vec(), cumulative.clone()));
+        cumulative
+    }
+
+    /// the running total of `weights`, with negative weights counted as 0.   Empty if they're all the same or add up to 0, as then every position is equally likely.
+    fn running_total(weights: &[f64]) -> Vec<f64> {
+        if weights.iter().all(|w| *w == weights[0]) {
+            return Vec::new();
+        }
+        let cumulative: Vec<f64> = weights
+            .iter()
+            .scan(0.0, |total, w| {
+                *total += w.max(0.0);
+                Some(*total)
+            })
+            .collect();
+        if cumulative[cumulative.len() - 1] > 0.0 {
+            cumulative
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl Clone for Sample {
+    fn clone(&self) -> Self {
+        Sample {
+            tries_per_game: self.tries_per_game.clone(),
+            tolerances: self.tolerances.clone(),
+            cumulative: RwLock::new(self.cumulative.read().unwrap().clone()),
+        }
     }
 }
 
-impl<const N: usize, const NSYMS: usize> Game<N, NSYMS> for Sample<N, NSYMS> {
+impl<S: Shape> Game<S> for Sample {
     fn run(
         &self,
-        left: &Candidate<N, NSYMS>,
-        right: &Candidate<N, NSYMS>,
+        left: &Candidate<S>,
+        right: &Candidate<S>,
         rng: &mut Rando,
         score_weights: &Vec<f64>,
     ) -> LeftRight {
@@ -41,15 +128,26 @@ impl<const N: usize, const NSYMS: usize> Game<N, NSYMS> for Sample<N, NSYMS> {
         let mut rpts = 0;
         let mut lpts = 0;
         let tries = rng.gen_range(self.tries_per_game.clone());
+        let cumulative = self.cumulative(score_weights);
 
         for i in 0..tries + 10 {
-            let pos = rng.gen_range(0..left.scores.len());
-            if left.scores[pos] < right.scores[pos] {
-                rpts += 1;
-            } else if left.scores[pos] > right.scores[pos] {
-                lpts += 1;
+            let pos = match cumulative.last() {
+                Some(total) => {
+                    let x = rng.gen_range_f64(0.0..*total);
+                    cumulative.partition_point(|t| *t <= x)
+                }
+                None => rng.gen_range(0..left.scores.len()),
+            };
+            match better(
+                left.scores[pos],
+                right.scores[pos],
+                tolerance(&self.tolerances, pos),
+            ) {
+                Some(LeftRight::Left) => lpts += 1,
+                Some(LeftRight::Right) => rpts += 1,
+                // if either left or right is NaN, or they're within tolerance, there's no winner, which is desired behaviour.
+                None => (),
             }
-            // if either left or right is NaN we fall through with no winner, which is desired behaviour.
 
             if rpts != lpts && i + 1 >= tries {
                 break;
@@ -66,6 +164,7 @@ impl<const N: usize, const NSYMS: usize> Game<N, NSYMS> for Sample<N, NSYMS> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chromosone::Fixed;
     const TRIES_PER_GAME: std::ops::Range<usize> = 1usize..4;
 
     #[test]
@@ -79,7 +178,7 @@ mod tests {
             .with(predicate::eq(0..9))
             .times(1)
             .return_const(2usize);
-        let g = Sample::<5, 3>::new(TRIES_PER_GAME);
+        let g: &dyn Game<Fixed<5, 3>> = &Sample::new(TRIES_PER_GAME);
         assert_eq!(
             LeftRight::Left,
             g.run(
@@ -99,10 +198,89 @@ mod tests {
         );
     }
 
+    fn weighted_game(
+        g: &dyn Game<Fixed<5, 3>>,
+        r: &mut Rando,
+        score_weights: &Vec<f64>,
+    ) -> LeftRight {
+        g.run(
+            &Candidate {
+                chromosone: [0, 0, 0, 0, 0],
+                scores: vec![1.0, 1.0, 0.0],
+                violations: 0,
+            },
+            &Candidate {
+                chromosone: [0, 0, 0, 0, 0],
+                scores: vec![0.0, 0.0, 1.0],
+                violations: 0,
+            },
+            r,
+            score_weights,
+        )
+    }
+
+    #[test]
+    fn test_game_weighted() {
+        let mut r = Rando::default();
+        r.expect_gen_range()
+            .with(predicate::eq(TRIES_PER_GAME))
+            .times(3)
+            .return_const(1usize);
+        // score 0 covers 0..1, score 1 nothing and score 2 1..6
+        r.expect_gen_range_f64()
+            .with(predicate::eq(0.0..6.0))
+            .times(2)
+            .return_const(1.0);
+        // equal weights try every score equally often
+        r.expect_gen_range()
+            .with(predicate::eq(0..3))
+            .times(1)
+            .return_const(0usize);
+        let g = Sample::new(TRIES_PER_GAME);
+        let weights = vec![1.0, 0.0, 5.0];
+        assert_eq!(LeftRight::Right, weighted_game(&g, &mut r, &weights));
+        // the second game reuses the running total
+        assert_eq!(LeftRight::Right, weighted_game(&g, &mut r, &weights));
+        assert_eq!(LeftRight::Left, weighted_game(&g, &mut r, &vec![1.0; 3]));
+    }
+
+    #[test]
+    fn test_game_fitness_weights() {
+        use crate::fitness::{LocusDesirability, WeightedCount};
+
+        let fitness = FitnessConfig::<Fixed<5, 3>>::new(vec![
+            Box::new(WeightedCount::new(1, vec![vec![1; 5]; 2])),
+            Box::new(LocusDesirability::new(vec![vec![1.0]; 5], 4.0)),
+        ]);
+        let mut r = Rando::default();
+        r.expect_gen_range()
+            .with(predicate::eq(TRIES_PER_GAME))
+            .times(1)
+            .return_const(1usize);
+        // the weights are [1, 1, 4], so score 0 covers 0..1, score 1 1..2 and score 2 2..6
+        r.expect_gen_range_f64()
+            .with(predicate::eq(0.0..6.0))
+            .times(1)
+            .return_const(2.0);
+        let g = Sample::weighted(TRIES_PER_GAME, &fitness);
+        assert_eq!(
+            LeftRight::Right,
+            weighted_game(&g, &mut r, &fitness.weights())
+        );
+    }
+
+    #[test]
+    fn test_running_total() {
+        assert_eq!(Sample::running_total(&[1.0, -1.0, 5.0]), [1.0, 1.0, 6.0]);
+        // all equal, or all 0, is uniform
+        assert!(Sample::running_total(&[2.0; 3]).is_empty());
+        assert!(Sample::running_total(&[0.0, -1.0]).is_empty());
+    }
+
     #[test]
     fn test_game_violations() {
         let mut r = Rando::default();
-        let g = Sample::<5, 3>::new(TRIES_PER_GAME);
+        let g: &dyn Game<Fixed<5, 3>> = &Sample::new(TRIES_PER_GAME);
         assert_eq!(
             LeftRight::Right,
             g.run(
@@ -133,7 +311,7 @@ mod tests {
             .with(predicate::eq(0..9))
             .times(11)
             .return_const(2usize);
-        let g = Sample::<5, 3>::new(TRIES_PER_GAME);
+        let g: &dyn Game<Fixed<5, 3>> = &Sample::new(TRIES_PER_GAME);
         assert_eq!(
             LeftRight::Left,
             g.run(
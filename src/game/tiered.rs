@@ -0,0 +1,195 @@
+use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+use crate::fitness::FitnessConfig;
+use crate::game::{better, tolerance, Game, LeftRight};
+
+#[mockall_double::double]
+use crate::rando::Rando;
+
+/**
+A lexicographic game: each score is assigned to a priority tier, and a lower tier is only looked at when every higher tier is tied, the same way violations are handled before any scores.
+
+The candidate with the least violations wins.   If that is equal, the scores in tier 0 are compared like in a [Full](super::Full) game: the candidate that is superior in the most scores, weighted by `score_weights`, wins the tier and the game.   If tier 0 is tied, tier 1 decides, and so on.   If every tier is tied the winner is random.
+
+No amount of wins in a lower tier can make up for a loss in a higher one, which weighted voting can't guarantee: a legal rest time preference always beats a "likes Tuesdays" preference.
+
+```
+# use gas::chromosone::Fixed;
+# use gas::fitness::{FitnessConfig, LocusDesirability, WeightedCount};
+# use gas::game::Tiered;
+let fitness = FitnessConfig::<Fixed<2, 2>>::new(vec![
+  Box::new(WeightedCount::new(1, vec![vec![1, 0], vec![0, 1]])),
+  Box::new(LocusDesirability::new(vec![vec![1.0, 0.0], vec![0.0, 1.0]], 1.0)),
+]);
+// the WeightedCount scores come first
+assert_eq!(Tiered::by_function(&fitness, &[0, 1]).tiers, vec![0, 0, 1, 1]);
+// its names start with "desirability"
+assert_eq!(Tiered::by_prefix(&fitness, &["desirability"]).tiers, vec![0, 0, 1, 1]);
+```
+ */
+#[derive(Debug, Clone)]
+pub struct Tiered {
+    /// `tiers[i]` is the tier of score `i`.   Tier 0 is compared first.
+    pub tiers: Vec<usize>,
+    /// how much each score must differ by to count.   Empty counts any difference.   [Tiered::by_function] and [Tiered::by_prefix] take them from the [tolerances](crate::fitness::FitnessFunction::tolerances) of the fitness functions.
+    pub tolerances: Vec<f64>,
+}
+
+impl Tiered {
+    /// `tiers` must have an entry for every score of the [FitnessConfig], `fitness.nscores` in all; a game between candidates with any other number of scores panics.   [Tiered::by_function] and [Tiered::by_prefix] build one that fits, and take the [tolerances](crate::fitness::FitnessFunction::tolerances) into account, which this doesn't.
+    pub const fn new(tiers: Vec<usize>) -> Tiered {
+        Tiered {
+            tiers,
+            tolerances: Vec::new(),
+        }
+    }
+
+    /// all the scores of `fitness.functions[f]` are in tier `function_tiers[f]`
+    pub fn by_function<S: Shape>(fitness: &FitnessConfig<S>, function_tiers: &[usize]) -> Tiered {
+        assert_eq!(fitness.functions.len(), function_tiers.len());
+        Tiered {
+            tiers: std::iter::zip(&fitness.functions, function_tiers)
+                .flat_map(|(func, tier)| std::iter::repeat_n(*tier, func.nscores()))
+                .collect(),
+            tolerances: fitness.tolerances(),
+        }
+    }
+
+    /// a score whose [FitnessName::prefix](crate::fitness::FitnessName::prefix) starts with `prefixes[t]` is in tier `t`.   The first matching prefix is used, and scores that match none, or whose function doesn't provide [names](crate::fitness::FitnessFunction::names), go in the last tier, `prefixes.len()`.
+    pub fn by_prefix<S: Shape>(fitness: &FitnessConfig<S>, prefixes: &[&str]) -> Tiered {
+        let mut tiers = Vec::<usize>::with_capacity(fitness.nscores);
+        for func in fitness.functions.iter() {
+            let names = func.names();
+            tiers.extend((0..func.nscores()).map(|i| {
+                names
+                    .get(i)
+                    .and_then(|name| prefixes.iter().position(|p| name.prefix.starts_with(p)))
+                    .unwrap_or(prefixes.len())
+            }));
+        }
+        Tiered {
+            tiers,
+            tolerances: fitness.tolerances(),
+        }
+    }
+}
+
+impl<S: Shape> Game<S> for Tiered {
+    fn run(
+        &self,
+        left: &Candidate<S>,
+        right: &Candidate<S>,
+        rng: &mut Rando,
+        score_weights: &Vec<f64>,
+    ) -> LeftRight {
+        assert_eq!(
+            self.tiers.len(),
+            left.scores.len(),
+            "a Tiered game needs a tier for every score"
+        );
+        if left.violations < right.violations {
+            return LeftRight::Left;
+        } else if left.violations > right.violations {
+            return LeftRight::Right;
+        }
+
+        let ntiers = self.tiers.iter().max().map_or(0, |t| t + 1);
+        let mut pts = vec![(0.0, 0.0); ntiers];
+        for (i, scores) in left.scores.iter().zip(right.scores.iter()).enumerate() {
+            let tier = &mut pts[self.tiers[i]];
+            match better(*scores.0, *scores.1, tolerance(&self.tolerances, i)) {
+                Some(LeftRight::Left) => tier.0 += score_weights[i],
+                Some(LeftRight::Right) => tier.1 += score_weights[i],
+                None => (),
+            }
+        }
+
+        for (l, r) in pts {
+            if l > r {
+                return LeftRight::Left;
+            } else if r > l {
+                return LeftRight::Right;
+            }
+        }
+        if rng.gen_range(0..2) == 0 {
+            LeftRight::Left
+        } else {
+            LeftRight::Right
+        }
+    }
+}
+
+#[cfg(test)]
+use mockall::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chromosone::Fixed;
+
+    fn candidate(scores: Vec<f64>) -> Candidate<Fixed<5, 3>> {
+        Candidate {
+            chromosone: [0, 0, 0, 0, 0],
+            scores,
+            violations: 0,
+        }
+    }
+
+    #[test]
+    fn test_tiered() {
+        let mut r = Rando::default();
+        let g: &dyn Game<Fixed<5, 3>> = &Tiered::new(vec![1, 0, 1, 1]);
+        // a single tier 0 win beats heavily weighted tier 1 losses
+        assert_eq!(
+            LeftRight::Right,
+            g.run(
+                &candidate(vec![1.0, 0.0, 1.0, 1.0]),
+                &candidate(vec![0.0, 1.0, 0.0, 0.0]),
+                &mut r,
+                &vec![10.0, 1.0, 10.0, 10.0],
+            )
+        );
+        // tier 0 is tied, so tier 1 decides
+        assert_eq!(
+            LeftRight::Left,
+            g.run(
+                &candidate(vec![1.0, 1.0, 1.0, 0.0]),
+                &candidate(vec![0.0, 1.0, 0.0, 1.0]),
+                &mut r,
+                &vec![1.0; 4],
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "a Tiered game needs a tier for every score")]
+    fn test_too_few_tiers() {
+        let mut r = Rando::default();
+        let g: &dyn Game<Fixed<5, 3>> = &Tiered::new(vec![0, 1]);
+        g.run(
+            &candidate(vec![1.0, 0.0, 1.0]),
+            &candidate(vec![0.0, 1.0, 0.0]),
+            &mut r,
+            &vec![1.0; 3],
+        );
+    }
+
+    #[test]
+    fn test_tiered_tied() {
+        let mut r = Rando::default();
+        r.expect_gen_range()
+            .with(predicate::eq(0..2))
+            .times(1)
+            .return_const(1usize);
+        let g: &dyn Game<Fixed<5, 3>> = &Tiered::new(vec![0, 0, 1]);
+        assert_eq!(
+            LeftRight::Right,
+            g.run(
+                &candidate(vec![1.0, 0.0, 2.0]),
+                &candidate(vec![0.0, 1.0, 2.0]),
+                &mut r,
+                &vec![1.0; 3],
+            )
+        );
+    }
+}
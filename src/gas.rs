@@ -1,54 +1,174 @@
+pub mod archive;
+pub mod checkpoint;
 pub mod cycle;
 pub mod generation;
+pub mod steady_state;
 
+#[cfg(test)]
+use crate::chromosone::Fixed;
+use crate::chromosone::Shape;
 use crate::constraints::ConstraintConfig;
 use crate::crossover::CrossoverConfig;
+use crate::domain::Domain;
 use crate::fitness::FitnessConfig;
 use crate::mutation::MutationConfig;
+use crate::observer::GenerationObserver;
+use crate::stopping::StoppingConfig;
+use crate::tournaments::elo::Glicko;
 use crate::tournaments::Tournament;
+use steady_state::SteadyState;
 
 /// see module documentation
-pub struct Gas<const N: usize, const NSYMS: usize> {
+pub struct Gas<S: Shape> {
+    /// the length of a chromosone and the number of symbols.  See [Shape].
+    pub shape: S,
     /// the set of fitness functions that turn a chromosone into a set of fitness scores
-    pub fitness: FitnessConfig<N, NSYMS>,
+    pub fitness: FitnessConfig<S>,
     /// constraints determine whether chromosones are valid or invalid
-    pub constraints: ConstraintConfig<N, NSYMS>,
+    pub constraints: ConstraintConfig<S>,
+    /// if set, [Candidate::new](crate::candidate::Candidate::new) only creates chromosones whose genes are allowed at their loci, and the mutations only create genes allowed at their loci.   See [domain](crate::domain).
+    pub domain: Option<Domain>,
     /// crossovers and constraints are the heart of a genetic algorithm.
-    pub crossovers: CrossoverConfig<N, NSYMS>,
+    pub crossovers: CrossoverConfig<S>,
     /// crossovers and constraints are the heart of a genetic algorithm.
-    pub mutations: MutationConfig<N, NSYMS>,
+    pub mutations: MutationConfig<S>,
     /// this is the tournament used in the algorithm, so is typically called millions of times.   faster, less accurate tournaments may therefore provide better results due to their speedup.
-    pub cycle_tournament: Box<dyn Tournament<N, NSYMS> + Send + Sync>,
+    pub cycle_tournament: Box<dyn Tournament<S> + Send + Sync>,
     /// used at the end of a cycle, a comprehensive tournament is best
-    pub final_tournament: Box<dyn Tournament<N, NSYMS> + Send + Sync>,
+    pub final_tournament: Box<dyn Tournament<S> + Send + Sync>,
     /// to ensure genetic diversity, the hamming distance between any two chromosones in the population must be at least this value
     pub taboo_distance: usize,
     pub population_size: usize,
+    /// the number of top rated candidates that [Gas::generation] carries over unchanged into the next generation.   1 carries over just the tournament winner.
+    pub elitism: usize,
+    /// the number of generations of [Gas::cycle_tournament] ratings averaged into the rating of a candidate that survives from one generation to the next, which [Gas::generation_rated] then uses to choose the elite and the parents.   1 uses only the latest tournament.   See [Rating](crate::tournaments::elo::Rating).   Ignored if [Gas::glicko] is set.
+    pub rating_memory: usize,
+    /// if set, the ratings carried between generations track their uncertainty, and parent selection takes it into account.   See [Glicko].
+    pub glicko: Option<Glicko>,
+    /// the most candidates kept in the Pareto archive, the best trade-offs between the scores found over the whole cycle.   0 disables it.  See [archive].
+    pub archive_size: usize,
+    /// if set, [Gas::cycle] replaces a few candidates at a time instead of running [Gas::generation].   See [steady_state].
+    pub steady_state: Option<SteadyState<S>>,
+    /// decides when each phase of a [Gas::cycle] ends.  [StoppingConfig::default()] is a good starting point.
+    pub stopping: StoppingConfig,
+    /// called by [Gas::cycle] after every generation, but not by a direct call to [Gas::generation].  See [observer](crate::observer).
+    pub observers: Vec<Box<dyn GenerationObserver<S> + Send + Sync>>,
+    /// the number of threads [Gas::generation] uses to run the fitness functions and constraints on its offspring.   1 evaluates them on the calling thread.   Offspring are always bred on the calling thread, so the results do not depend on this setting.
+    pub evaluation_threads: usize,
 }
 
 #[cfg(test)]
-impl Gas<5, 3> {
+impl Gas<Fixed<5, 3>> {
     /// device under test
-    pub fn dut() -> Gas<5, 3> {
+    pub fn dut() -> Gas<Fixed<5, 3>> {
         use crate::fitness::distance::Distance;
         use crate::game::full::Full;
-        use crate::mutation::mutate::Mutate;
         use crate::tournaments::single_elimination::SingleElimination;
 
         Gas {
-            fitness: FitnessConfig::new(vec![Box::new(Distance::<5, 3>::new(
-                7, [None; 3], [None; 3], 1.0, 1.0,
-            ))]),
-            constraints: ConstraintConfig::new(vec![]),
-            cycle_tournament: Box::new(SingleElimination::new(Full::new())),
             final_tournament: Box::new(SingleElimination::new(Full::new())),
             crossovers: CrossoverConfig::new(vec![(
                 1,
                 Box::new(crate::crossover::null::Null::new()),
             )]),
-            mutations: MutationConfig::new(vec![(1, Box::new(Mutate::<5, 3>::new(1)))]),
-            taboo_distance: 1,
             population_size: 3,
+            ..Gas::new(
+                Fixed::new(),
+                FitnessConfig::new(vec![Box::new(Distance::new(
+                    7,
+                    vec![None; 3],
+                    vec![None; 3],
+                    1.0,
+                    1.0,
+                ))]),
+            )
+        }
+    }
+}
+
+impl<S: Shape> Gas<S> {
+    /// a Gas for `shape` and `fitness` with everything else set to a reasonable starting point, to be overridden with struct update syntax: `Gas { population_size: 100, ..Gas::new(shape, fitness) }`.   No constraints or domain, a [Splice](crate::crossover::Splice) crossover, a [Mutate](crate::mutation::Mutate) of one gene, a [SingleElimination](crate::tournaments::SingleElimination) each generation and a [FullSeason](crate::tournaments::FullSeason) at the end, a population of 10 with just the winner carried over, no archive, generational replacement, [StoppingConfig::default()], no observers and a single evaluation thread.
+    pub fn new(shape: S, fitness: FitnessConfig<S>) -> Gas<S> {
+        use crate::crossover::Splice;
+        use crate::game::Full;
+        use crate::mutation::Mutate;
+        use crate::tournaments::{FullSeason, SingleElimination};
+
+        Gas {
+            shape,
+            fitness,
+            constraints: ConstraintConfig::new(vec![]),
+            domain: None,
+            cycle_tournament: Box::new(SingleElimination::new(Full::new())),
+            final_tournament: Box::new(FullSeason::new(Full::new())),
+            crossovers: CrossoverConfig::new(vec![(1, Box::new(Splice::new()))]),
+            mutations: MutationConfig::new(vec![(1, Box::new(Mutate::new(1, shape.nsyms())))]),
+            taboo_distance: 1,
+            population_size: 10,
+            elitism: 1,
+            rating_memory: 1,
+            glicko: None,
+            archive_size: 0,
+            steady_state: None,
+            stopping: StoppingConfig::default(),
+            observers: vec![],
+            evaluation_threads: 1,
         }
     }
+
+    /// panics if parts of the configuration disagree with each other, rather than letting them fail obscurely part way through a cycle: for instance a [Mutate](crate::mutation::Mutate) with more or fewer symbols than the shape, a [Domain] that doesn't fit it, or a [Glicko] with no tournament deviation.   Called at the start of every [Gas::cycle].
+    pub fn validate(&self) {
+        if let Some(domain) = &self.domain {
+            domain.check(&self.shape);
+        }
+        for (_, mutation) in self.mutations.mutations_with_weights.iter() {
+            if let Some(nsyms) = mutation.nsyms() {
+                assert_eq!(
+                    nsyms,
+                    self.shape.nsyms(),
+                    "a mutation has a different number of symbols to the shape"
+                );
+            }
+        }
+        if let Some(glicko) = &self.glicko {
+            glicko.check();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutation::Mutate;
+
+    #[test]
+    #[should_panic(expected = "a mutation has a different number of symbols to the shape")]
+    fn test_validate_nsyms() {
+        let mut gas = Gas::dut();
+        gas.validate();
+        gas.mutations = MutationConfig::new(vec![(1, Box::new(Mutate::new(1, 4)))]);
+        gas.validate();
+    }
+
+    #[test]
+    #[should_panic(expected = "the domain has a different number of loci to the shape")]
+    fn test_validate_domain() {
+        let mut gas = Gas::dut();
+        gas.domain = Some(Domain::new(vec![vec![0, 1, 2]; 4]));
+        gas.validate();
+    }
+
+    #[test]
+    #[should_panic(expected = "the tournament deviation must be greater than 0")]
+    fn test_validate_glicko() {
+        let mut gas = Gas::dut();
+        gas.glicko = Some(Glicko::new(100.0, 10.0, 1.0));
+        gas.validate();
+        gas.glicko = Some(Glicko {
+            tournament_deviation: 0.0,
+            drift: 10.0,
+            confidence: 1.0,
+        });
+        gas.validate();
+    }
 }
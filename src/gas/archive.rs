@@ -0,0 +1,156 @@
+/*!
+
+An [Archive] is a hall of fame: the best trade-offs between the fitness scores found over a whole [Gas::cycle].
+
+Every generation each member of the population is offered to the archive, which keeps the candidates that no other candidate it has seen [dominates](Candidate::dominates), one per set of scores.   When there are more than [Gas::archive_size] of those, the most crowded (see [Candidate::crowding]) are dropped so that the archive stays spread out along the front.   At the end of the cycle the archive joins the winners in the [Gas::final_tournament].
+
+Rather than a single winner, the archive is the set of trade-offs to choose from.   It is kept up to date in [CycleProgress::archive], and [Pool::archive] merges the archives of all the threads of a [Pool].
+
+```
+# use gas::Gas;
+# use gas::candidate::Candidate;
+# use gas::chromosone::{Fixed, GeneType, Shape};
+# use gas::fitness::{FitnessConfig, FitnessFunction};
+# use gas::gas::cycle::CycleProgress;
+# use gas::rando::Rando;
+# use gas::stopping::*;
+# use std::sync::atomic::AtomicBool;
+# use std::sync::Arc;
+// two conflicting scores: the number of 0's and the number of 5's
+struct ZerosAndFives {}
+
+impl<S: Shape> FitnessFunction<S> for ZerosAndFives {
+  fn nscores(&self) -> usize { 2 }
+
+  fn run(&self, chromosone: &S::Chromosone) -> Vec<f64> {
+    let count = |sym| chromosone.as_ref().iter().filter(|g| g.to_usize() == sym).count() as f64;
+    vec![count(0), count(5)]
+  }
+}
+
+let gas = Gas {
+  archive_size: 10,
+#  stopping: StoppingConfig {
+#    stop: Box::new(MaxGenerations::new(100)),
+#    ..StoppingConfig::default()
+#  },
+  ..Gas::new(Fixed::<4, 6>::new(), FitnessConfig::new(vec![Box::new(ZerosAndFives {})]))
+};
+let sigint = Arc::new(AtomicBool::new(false));
+let progress = CycleProgress::new(&gas, &sigint);
+gas.cycle(&mut progress.clone(), Rando::seed_from_u64(1));
+
+// the front is the chromosones of only 0's and 5's, one for each number of 0's
+let archive = progress.archive.read().unwrap();
+assert_eq!(archive.len(), 5);
+assert!(archive.iter().all(|c| c.chromosone.iter().all(|g| *g == 0 || *g == 5)));
+```
+
+ */
+
+use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+
+#[cfg(doc)]
+use crate::{
+    gas::{cycle::CycleProgress, Gas},
+    pool::Pool,
+};
+
+/// see module documentation
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct Archive<S: Shape> {
+    /// the most members kept.   0 disables the archive.
+    pub capacity: usize,
+    /// no member dominates another, and no two have the same chromosone or the same scores
+    pub members: Vec<Candidate<S>>,
+}
+
+impl<S: Shape> Archive<S> {
+    pub fn new(capacity: usize) -> Archive<S> {
+        Archive {
+            capacity,
+            members: Vec::with_capacity(capacity + 1),
+        }
+    }
+
+    /// add `candidate` unless it duplicates a member or a member dominates it, dropping any members it dominates.   Returns whether it was added.   If that takes the archive over capacity the most crowded member is dropped, which may be `candidate` itself.
+    pub fn offer(&mut self, candidate: &Candidate<S>) -> bool {
+        if self.capacity == 0
+            || self
+                .members
+                .iter()
+                .any(|m| Archive::duplicate(m, candidate) || m.dominates(candidate))
+        {
+            return false;
+        }
+        self.members.retain(|m| !candidate.dominates(m));
+        self.members.push(candidate.clone());
+        if self.members.len() > self.capacity {
+            let crowding = Candidate::crowding(&self.members);
+            let most_crowded = (0..crowding.len())
+                .min_by(|a, b| crowding[*a].total_cmp(&crowding[*b]))
+                .unwrap();
+            self.members.remove(most_crowded);
+            return most_crowded != self.members.len();
+        }
+        true
+    }
+
+    /// the same chromosone, or the same violations and scores.   Only one of a set of candidates with the same scores is worth keeping.
+    fn duplicate(member: &Candidate<S>, candidate: &Candidate<S>) -> bool {
+        member.chromosone == candidate.chromosone
+            || (member.violations == candidate.violations
+                && std::iter::zip(&member.scores, &candidate.scores)
+                    .all(|(a, b)| a.total_cmp(b).is_eq()))
+    }
+
+    /// offer every member of `population`
+    pub fn update(&mut self, population: &[Candidate<S>]) {
+        for candidate in population {
+            self.offer(candidate);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chromosone::Fixed;
+
+    fn candidate(gene: u8, scores: Vec<f64>) -> Candidate<Fixed<5, 3>> {
+        Candidate {
+            chromosone: [gene; 5],
+            scores,
+            violations: 0,
+        }
+    }
+
+    #[test]
+    fn test_archive() {
+        let mut archive = Archive::new(3);
+        assert!(archive.offer(&candidate(0, vec![1.0, 1.0])));
+        // duplicates
+        assert!(!archive.offer(&candidate(0, vec![1.0, 1.0])));
+        assert!(!archive.offer(&candidate(2, vec![1.0, 1.0])));
+        // dominates the first
+        assert!(archive.offer(&candidate(1, vec![2.0, 1.0])));
+        assert_eq!(archive.members.len(), 1);
+        // dominated
+        assert!(!archive.offer(&candidate(2, vec![2.0, 0.0])));
+        archive.update(&[candidate(3, vec![0.0, 4.0]), candidate(4, vec![1.0, 3.0])]);
+        assert_eq!(archive.members.len(), 3);
+        // over capacity, and it is the most crowded
+        assert!(!archive.offer(&candidate(5, vec![1.5, 2.5])));
+        let chromosones: Vec<u8> = archive.members.iter().map(|m| m.chromosone[0]).collect();
+        assert_eq!(chromosones, vec![1, 3, 4]);
+
+        let mut disabled = Archive::new(0);
+        assert!(!disabled.offer(&candidate(0, vec![1.0, 1.0])));
+    }
+}
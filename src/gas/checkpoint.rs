@@ -0,0 +1,251 @@
+/*!
+
+A [Checkpoint] is a snapshot of everything a running [Gas::cycle] needs to carry on: the population, the seed pool, the winners sampled so far, the Pareto [Archive], the [CycleStats] (iteration, moving averages, stagnation counters), the adaptive [OperatorWeights] of [Gas::crossovers] and [Gas::mutations], and the state of the [Rando].   [Gas::resume] picks up from a checkpoint as if the cycle had never stopped, so a seeded run that is checkpointed and resumed gives the same result as one that ran straight through.
+
+A running cycle writes a checkpoint into [CycleProgress::checkpoint] every [CycleProgress::checkpoint_interval] generations, and when [CycleProgress::sigint] is set.   With the `serde` feature enabled a checkpoint can be written to disk as JSON or any other serde format.
+
+```
+# use gas::Gas;
+# use gas::chromosone::Fixed;
+# use gas::fitness::{self, FitnessConfig};
+# use gas::gas::cycle::CycleProgress;
+# use gas::rando::Rando;
+# use gas::stopping::*;
+# use std::sync::atomic::{AtomicBool, Ordering};
+# use std::sync::Arc;
+fn gas(generations: usize) -> Gas<Fixed<4, 6>> {
+  Gas {
+    stopping: StoppingConfig {
+      stop: Box::new(MaxGenerations::new(generations)),
+      ..StoppingConfig::default()
+    },
+    ..Gas::new(
+      Fixed::<4, 6>::new(),
+      FitnessConfig::new(vec![
+        Box::new(fitness::LocusDesirability::new(vec![vec![1.0, 0.0, 0.0, 2.0]; 6], 1.0)),
+      ]),
+    )
+  }
+}
+let sigint = Arc::new(AtomicBool::new(false));
+let straight_through = gas(60).cycle(&mut CycleProgress::new(&gas(60), &sigint), Rando::seed_from_u64(3));
+
+// the first run is cut short after 30 generations, leaving a checkpoint behind
+let mut progress = CycleProgress::new(&gas(30), &sigint);
+progress.checkpoint_interval.store(10, Ordering::Relaxed);
+gas(30).cycle(&mut progress, Rando::seed_from_u64(3));
+let checkpoint = progress.checkpoint.lock().unwrap().take().unwrap();
+assert_eq!(checkpoint.stats.iteration, 30);
+
+# #[cfg(feature = "serde")]
+# let checkpoint = serde_json::from_str(&serde_json::to_string(&checkpoint).unwrap()).unwrap();
+let resumed = gas(60).resume(checkpoint, &mut CycleProgress::new(&gas(60), &sigint));
+assert_eq!(resumed.chromosone, straight_through.chromosone);
+```
+
+## Warm starts
+
+[Checkpoint::warm_start] builds the starting point of a cycle from chromosones you already have, such as a previous solution or hand edited drafts, rather than from scratch.   The [WarmStart] decides where they go, including whether to skip seeding altogether.   [Gas::warm_start] runs a cycle from one, and for a [Pool](crate::pool::Pool) pass one per thread to [Pool::resume](crate::pool::Pool::resume).
+
+```
+# use gas::Gas;
+# use gas::chromosone::Fixed;
+# use gas::fitness::{self, FitnessConfig};
+# use gas::gas::checkpoint::{Checkpoint, WarmStart};
+# use gas::gas::cycle::{CycleProgress, State};
+# use gas::rando::Rando;
+# use gas::stopping::*;
+# use std::sync::atomic::AtomicBool;
+# use std::sync::Arc;
+# let desirability = (0..6).map(|g| (0..4).map(|i| if g == i { 1.0 } else { 0.0 }).collect()).collect();
+# let gas = Gas {
+#   stopping: StoppingConfig {
+#     stop: Box::new(MaxGenerations::new(20)),
+#     ..StoppingConfig::default()
+#   },
+#   ..Gas::new(
+#     Fixed::<4, 6>::new(),
+#     FitnessConfig::new(vec![Box::new(fitness::LocusDesirability::new(desirability, 1.0))]),
+#   )
+# };
+// last week's solution and a draft that is one change away from the best, [0, 1, 2, 3]
+let previous = vec![[0, 1, 2, 5], [0, 1, 2, 3]];
+
+let checkpoint = Checkpoint::warm_start(&gas, &previous, WarmStart::SeedPool, Rando::seed_from_u64(1));
+assert_eq!(checkpoint.stats.state, State::Seeding);
+assert_eq!(checkpoint.seed_pool[0].chromosone, [0, 1, 2, 3]);
+
+let sigint = Arc::new(AtomicBool::new(false));
+let winner = gas.warm_start(
+    &previous,
+    WarmStart::SkipSeeding,
+    &mut CycleProgress::new(&gas, &sigint),
+    Rando::seed_from_u64(1),
+);
+assert_eq!(winner.chromosone, [0, 1, 2, 3]);
+```
+
+ */
+
+use super::archive::Archive;
+use super::cycle::{CycleStats, State};
+use super::Gas;
+use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+use crate::operator_weights::OperatorStats;
+#[mockall_double::double]
+use crate::rando::Rando;
+use crate::tournaments::elo::Rating;
+
+#[cfg(doc)]
+use super::cycle::CycleProgress;
+#[cfg(doc)]
+use crate::operator_weights::OperatorWeights;
+
+/// see module documentation
+#[derive(Clone)]
+#[cfg_attr(
+    all(feature = "serde", not(test)),
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct Checkpoint<S: Shape> {
+    /// the current population.  `population[0]` is the winner of the most recent tournament.
+    pub population: Vec<Candidate<S>>,
+    /// the seeds found so far.  Only grows while [State::Seeding].
+    pub seed_pool: Vec<Candidate<S>>,
+    /// the sampling of generation winners for the final tournament
+    pub winners: Vec<Candidate<S>>,
+    pub stats: CycleStats,
+    /// the Pareto archive.  See [archive](super::archive).
+    pub archive: Archive<S>,
+    /// the ratings of `population` carried between steps by a [SteadyState](super::steady_state::SteadyState) cycle.  Empty otherwise.
+    pub ratings: Vec<usize>,
+    /// the accumulated ratings of `population` carried between generations by [Gas::generation_rated].   Empty for a [SteadyState](super::steady_state::SteadyState) cycle.
+    pub history: Vec<Rating>,
+    /// the adaptation state of [Gas::crossovers], restored into it by [Gas::resume].   The threads of a [Pool](crate::pool::Pool) share their [Gas], so [Pool::resume](crate::pool::Pool::resume) restores the state from the first checkpoint, once, before any thread starts.
+    pub crossover_stats: OperatorStats,
+    /// the adaptation state of [Gas::mutations], likewise
+    pub mutation_stats: OperatorStats,
+    pub rng: Rando,
+}
+
+/// where [Checkpoint::warm_start] puts the chromosones it is given
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WarmStart {
+    /// into the first population of [State::Seeding], topped up with random candidates.   The first seed is evolved from them.
+    Population,
+    /// into the seed pool.   Seeding carries on with random populations until the pool is full.   Like any seeds they are dropped from the pool if seeding finds a seed with fewer violations.
+    SeedPool,
+    /// into the population, topped up with random candidates, and go straight to [State::Running]
+    SkipSeeding,
+}
+
+#[cfg_attr(test, allow(dead_code))]
+impl<S: Shape> Checkpoint<S> {
+    /// the starting point of a [Gas::cycle]: a random population at the start of [State::Seeding].
+    pub fn new(gas: &Gas<S>, mut rng: Rando) -> Checkpoint<S> {
+        gas.validate();
+        let population = Checkpoint::fill(gas, vec![], &mut rng);
+        Checkpoint::start(gas, population, None, State::Seeding, rng)
+    }
+
+    /// the starting point of a [Gas::warm_start].   At most [Gas::population_size] of `chromosones` are used, and duplicates are ignored.   See the module documentation.
+    pub fn warm_start(
+        gas: &Gas<S>,
+        chromosones: &[S::Chromosone],
+        warm: WarmStart,
+        mut rng: Rando,
+    ) -> Checkpoint<S> {
+        gas.validate();
+        let score_weights = gas.fitness.weights();
+        let mut supplied = Vec::<Candidate<S>>::with_capacity(gas.population_size);
+        for chromosone in chromosones {
+            if supplied.len() < gas.population_size
+                && supplied.iter().all(|c| c.chromosone != *chromosone)
+            {
+                supplied.push(Candidate::from_chromosone(gas, chromosone.clone()));
+            }
+        }
+        // best first, so that population[0] and seed_pool[0] are what the cycle expects
+        supplied.sort_by(|a, b| {
+            a.violations.cmp(&b.violations).then(
+                b.total_score(&score_weights)
+                    .total_cmp(&a.total_score(&score_weights)),
+            )
+        });
+
+        match warm {
+            WarmStart::SeedPool if supplied.len() == gas.population_size => {
+                let seed_pool = supplied.clone();
+                Checkpoint::start(gas, supplied, Some(seed_pool), State::Running, rng)
+            }
+            WarmStart::SeedPool if !supplied.is_empty() => {
+                let population = Checkpoint::fill(gas, vec![], &mut rng);
+                Checkpoint::start(gas, population, Some(supplied), State::Seeding, rng)
+            }
+            WarmStart::SkipSeeding => {
+                let seed_pool = supplied.clone();
+                let population = Checkpoint::fill(gas, supplied, &mut rng);
+                Checkpoint::start(gas, population, Some(seed_pool), State::Running, rng)
+            }
+            _ => {
+                let population = Checkpoint::fill(gas, supplied, &mut rng);
+                Checkpoint::start(gas, population, None, State::Seeding, rng)
+            }
+        }
+    }
+
+    /// top up `population` with random candidates
+    fn fill(gas: &Gas<S>, mut population: Vec<Candidate<S>>, rng: &mut Rando) -> Vec<Candidate<S>> {
+        population.reserve(gas.population_size.saturating_sub(population.len()));
+        while population.len() < gas.population_size {
+            population.push(Candidate::new(gas, rng));
+        }
+        population
+    }
+
+    fn start(
+        gas: &Gas<S>,
+        population: Vec<Candidate<S>>,
+        seed_pool: Option<Vec<Candidate<S>>>,
+        state: State,
+        rng: Rando,
+    ) -> Checkpoint<S> {
+        let score_weights = gas.fitness.weights();
+        let ts = population[0].total_score(&score_weights);
+        let stats = CycleStats {
+            state,
+            evaluations: population.len(),
+            score: ts,
+            violations: population[0].violations,
+            record_score: ts,
+            record_violations: population[0].violations,
+            cur_violations: population[0].violations,
+            n_cur_violations: 1,
+            stagnation_iteration: 1,
+            emas: gas.stopping.emas().iter().map(|c| (*c, ts)).collect(),
+            ..Default::default()
+        };
+
+        // seed so on sigint it's not empty
+        let mut winners = Vec::<Candidate<S>>::with_capacity(population.len());
+        winners.push(population[0].clone());
+        let seed_pool = seed_pool.unwrap_or_else(|| vec![population[0].clone()]);
+        let mut archive = Archive::new(gas.archive_size);
+        archive.update(&population);
+
+        Checkpoint {
+            population,
+            seed_pool,
+            winners,
+            stats,
+            archive,
+            ratings: vec![],
+            history: vec![],
+            crossover_stats: gas.crossovers.weights.stats(),
+            mutation_stats: gas.mutations.weights.stats(),
+            rng,
+        }
+    }
+}
@@ -1,36 +1,54 @@
+use super::checkpoint::{Checkpoint, WarmStart};
 use super::Gas;
 use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+use crate::pool::migration::Island;
 #[mockall_double::double]
 use crate::rando::Rando;
+use crate::tournaments::elo::Rating;
 
 use std::sync::{
     atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering},
-    Arc, RwLock,
+    Arc, Mutex, RwLock,
 };
+use std::time::{Duration, Instant};
+
+#[cfg(doc)]
+use super::archive::Archive;
+#[cfg(doc)]
+use crate::stopping::StoppingConfig;
 
 #[cfg_attr(test, allow(dead_code))]
 /** Communication between a [Gas.cycle] running in a thread and the main thread.   This allows the GA algorithm to be monitored during execution.  */
-pub struct CycleProgress<const N: usize, const NSYMS: usize> {
+pub struct CycleProgress<S: Shape> {
     /// out: the number of iterations of the GA that have been run
     pub iteration: Arc<AtomicUsize>,
     /// out: continually updated with a rounded integer of [Candidate#total_score] of the best candidate
     pub score: Arc<AtomicIsize>,
     /// out: continually updated with the number of constraint violations of the best candidate
     pub violations: Arc<AtomicUsize>,
-    /// out: continually updated with progress, values 0-100.   Does not increment until stagnation is detected.
+    /// out: continually updated with progress, values 0-100.   Calculated from the [StoppingConfig::stop] criterion and, once stagnation is detected, from the number of winners sampled.
     pub progress: Arc<AtomicUsize>,
     /// out: copy of the top candidate
-    pub top: Arc<RwLock<Candidate<N, NSYMS>>>,
-    /// in: SIGINT or similar.  if set, cycle will finish and exit ASAP
+    pub top: Arc<RwLock<Candidate<S>>>,
+    /// out: copy of the members of the Pareto [Archive], updated every generation.   Empty if [Gas::archive_size] is 0.
+    pub archive: Arc<RwLock<Vec<Candidate<S>>>>,
+    /// in: SIGINT or similar.  if set, cycle will write a [Checkpoint], finish and exit ASAP
     pub sigint: Arc<AtomicBool>,
+    /// in: write a [Checkpoint] every this many generations.   0, the default, only writes one on sigint.
+    pub checkpoint_interval: Arc<AtomicUsize>,
+    /// out: the most recent [Checkpoint].   [Option::take] it to save it; [Gas::resume] carries on from it.
+    pub checkpoint: Arc<Mutex<Option<Checkpoint<S>>>>,
+    /// in: set by [Pool::islands](crate::pool::Pool::islands) to exchange candidates with the other threads.  See [migration](crate::pool::migration).
+    pub island: Option<Island<S>>,
 
     pub seed_pool_size: Arc<AtomicUsize>,
     pub diversity_violations: Arc<AtomicUsize>,
 }
 
 #[cfg_attr(test, allow(dead_code))]
-impl<const N: usize, const NSYMS: usize> CycleProgress<N, NSYMS> {
-    pub fn new(gas: &Gas<N, NSYMS>, sigint: &Arc<AtomicBool>) -> CycleProgress<N, NSYMS> {
+impl<S: Shape> CycleProgress<S> {
+    pub fn new(gas: &Gas<S>, sigint: &Arc<AtomicBool>) -> CycleProgress<S> {
         CycleProgress {
             iteration: Arc::new(AtomicUsize::new(0)),
             score: Arc::new(AtomicIsize::new(0)),
@@ -38,13 +56,20 @@ impl<const N: usize, const NSYMS: usize> CycleProgress<N, NSYMS> {
             progress: Arc::new(AtomicUsize::new(0)),
             seed_pool_size: Arc::new(AtomicUsize::new(0)),
             diversity_violations: Arc::new(AtomicUsize::new(0)),
-            top: Arc::new(RwLock::new(Candidate::from_chromosone(gas, [0; N]))),
+            top: Arc::new(RwLock::new(Candidate::from_chromosone(
+                gas,
+                gas.shape.chromosone(|_| S::Gene::default()),
+            ))),
+            archive: Arc::new(RwLock::new(vec![])),
             sigint: Arc::clone(&sigint),
+            checkpoint_interval: Arc::new(AtomicUsize::new(0)),
+            checkpoint: Arc::new(Mutex::new(None)),
+            island: None,
         }
     }
 
     /// Arc::clone all the Arc's.   So like Arc::clone, doesn't actually clone the contents of the CycleProgress, just the wrapper.
-    pub fn clone(&self) -> CycleProgress<N, NSYMS> {
+    pub fn clone(&self) -> CycleProgress<S> {
         CycleProgress {
             iteration: Arc::clone(&self.iteration),
             score: Arc::clone(&self.score),
@@ -53,7 +78,11 @@ impl<const N: usize, const NSYMS: usize> CycleProgress<N, NSYMS> {
             seed_pool_size: Arc::clone(&self.seed_pool_size),
             diversity_violations: Arc::clone(&self.diversity_violations),
             top: Arc::clone(&self.top),
+            archive: Arc::clone(&self.archive),
             sigint: Arc::clone(&self.sigint),
+            checkpoint_interval: Arc::clone(&self.checkpoint_interval),
+            checkpoint: Arc::clone(&self.checkpoint),
+            island: self.island.clone(),
         }
     }
 
@@ -70,6 +99,86 @@ impl<const N: usize, const NSYMS: usize> CycleProgress<N, NSYMS> {
     }
 }
 
+/// The phases of a [Gas::cycle].  See [Gas::cycle] for details.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum State {
+    /// building up the seed pool from independent random populations
+    #[default]
+    Seeding,
+    /// evolving the seed pool until [StoppingConfig::stagnated] fires
+    Running,
+    /// sampling winners for the final tournament
+    Stagnated,
+}
+
+/// Statistics about a running [Gas::cycle], updated every generation.   The [StoppingCriterion](crate::stopping::StoppingCriterion)'s make their decisions from these.
+///
+/// "top" is the winner of the most recent tournament, aka `population[0]`.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CycleStats {
+    pub state: State,
+    /// the number of generations run so far
+    pub iteration: usize,
+    /// the number of candidates created so far.   Each creation runs every fitness function and constraint.
+    pub evaluations: usize,
+    /// wall clock time the cycle has been running, not counting time between a [Checkpoint] and [Gas::resume]
+    pub elapsed: Duration,
+    /// [Candidate::total_score] of the top candidate
+    pub score: f64,
+    /// constraint violations of the top candidate
+    pub violations: usize,
+    /// the best score the top candidate has had since the end of seeding.
+    pub best_score: f64,
+    /// the fewest violations the top candidate has had this cycle
+    pub record_violations: usize,
+    /// the best score the top candidate has had with `record_violations`
+    pub record_score: f64,
+    /// the iteration at which the top candidate last beat the record: fewer violations, or the same violations and a higher score.
+    pub last_improvement: usize,
+    /// the current number of violations, tracked by `n_cur_violations`
+    pub cur_violations: usize,
+    /// the number of generations the top candidate's violations have stayed at the current value
+    pub n_cur_violations: usize,
+    /// the iteration at which the cycle stagnated.
+    pub stagnation_iteration: usize,
+    /// exponential moving averages of the top score as `(smoothing constant, average)` pairs.   Only updated while [State::Running].   See [CycleStats::ema].
+    pub emas: Vec<(f64, f64)>,
+}
+
+impl CycleStats {
+    /// the exponential moving average of the top score for the smoothing constant `constant`, or None if no [StoppingCriterion::emas](crate::stopping::StoppingCriterion::emas) requested it.
+    pub fn ema(&self, constant: f64) -> Option<f64> {
+        self.emas
+            .iter()
+            .find(|(c, _)| *c == constant)
+            .map(|(_, ema)| *ema)
+    }
+
+    /// make sure there is an average for each of `constants`, for instance when a [Checkpoint] is resumed with a different [StoppingConfig].   New averages start at the current score.
+    pub fn track_emas(&mut self, constants: &[f64]) {
+        for c in constants {
+            if self.ema(*c).is_none() {
+                self.emas.push((*c, self.score));
+            }
+        }
+    }
+
+    /// update the records with a new top candidate.
+    fn record(&mut self, score: f64, violations: usize) {
+        self.score = score;
+        self.violations = violations;
+        if violations < self.record_violations
+            || (violations == self.record_violations && score > self.record_score)
+        {
+            self.record_violations = violations;
+            self.record_score = score;
+            self.last_improvement = self.iteration;
+        }
+    }
+}
+
 /**
  ** Given a population, run multiple [Gas.generation]'s of the algorithm until it stagnates and then return the winner.
  **
@@ -79,73 +188,110 @@ impl<const N: usize, const NSYMS: usize> CycleProgress<N, NSYMS> {
  ** There are four phases to the algorithm.
  **
  ** 1. Seeding: Starting with a set of random candidates, iterate [Gas.generation] until the top
- ** candidate either has zero constraint violations or the number of constraint
- ** violations has stabilized. Add the candidate to the seed and then restart
+ ** candidate either has zero constraint violations or [StoppingConfig::seeded] fires.
+ ** Add the candidate to the seed and then restart
  ** with another set of random candidates. Repeat until you've accumulated
  ** population_size number of seeds.
  **
- **  2. Running: Iterate [Gas.generation] until both the score and number of violations has stagnated.
+ **  2. Running: Iterate [Gas.generation] until [StoppingConfig::stagnated] fires.   By default that is when both the score and number of violations has stagnated.
  **
  ** 3. Stagnated: Keep running the GA, doing a biased sampling of the winners
- ** until we've got enough samples or we've been doing this step for too long.
+ ** until we've got enough samples.
+ **
+ ** 4. Do a final tournament of the winners do get the grand winner, which we return.   If [Gas::archive_size] is set, the members of the Pareto [Archive] maintained over the whole cycle join the winners.   See [archive](super::archive).
+ **
+ ** After every generation each of the [Gas::observers] is called.
+ **
+ ** If [CycleProgress::island] is set, the best candidates are exchanged with the other islands every [Migration::interval](crate::pool::migration::Migration::interval) generations once seeding is complete.
  **
- ** 4. Do a final tournament of the winners do get the grand winner, which we return.
+ ** [StoppingConfig::stop] is checked in every phase, and jumps straight to the final tournament when it fires.   If that happens before the cycle stagnates, the current top candidate (and while seeding, the seed pool) joins the winners.
+ **
+ ** All randomness comes from `rng`, so passing a [Rando::seed_from_u64] makes the cycle reproducible.
+ **
+ ** The state of the cycle is saved into [CycleProgress::checkpoint] as configured by [CycleProgress::checkpoint_interval].  [Gas::resume] continues a cycle from a [Checkpoint].
  **
  **/
-impl<const N: usize, const NSYMS: usize> Gas<N, NSYMS> {
+impl<S: Shape> Gas<S> {
     #[cfg_attr(test, allow(dead_code))]
-    pub fn cycle(&self, progress: &mut CycleProgress<N, NSYMS>) -> Candidate<N, NSYMS> {
-        let score_weights = self.fitness.weights();
-        let mut population = Vec::<Candidate<N, NSYMS>>::with_capacity(self.population_size);
-        let mut rng = Rando::new();
-        let mut seed_pool = Vec::<Candidate<N, NSYMS>>::new();
-
-        for _ in 0..self.population_size {
-            population.push(Candidate::new(self, &mut rng));
-        }
-
-        // fast moving average of score.  Stagnation of score is defined as when this goes below the slow moving average.
-        let mut ema99 = population[0].total_score(&score_weights);
-        // slow moving average of score
-        let mut ema999 = ema99;
-        // number of violations of current winner
-        let mut cur_violations = population[0].violations;
-        // how long the number of violations has stayed stable.  Stagnation is defined as when this reaches VIOLATIONS_STAGNATION_THRESHOLD
-        let mut n_cur_violations = 1;
-        // best score ever seen
-        let mut best_score = 0f64;
-        // a sampling of generation winners for the final tournament
-        let mut winners = Vec::<Candidate<N, NSYMS>>::with_capacity(population.len());
-        // when state flipped
-        let mut stagnation_iteration = 1usize;
+    pub fn cycle(&self, progress: &mut CycleProgress<S>, rng: Rando) -> Candidate<S> {
+        self.run_cycle(Checkpoint::new(self, rng), progress)
+    }
 
-        // Stagnation tuning parameters.
-        /// the exponential smoothing constant for the fast moving average
-        const EMA_FAST_CONST: f64 = 0.995;
-        /// the exponential smoothing constant for the slow moving average
-        const EMA_SLOW_CONST: f64 = 0.9995;
-        /// how long the number of violations can stay at a single value before it is considered stagnate.   Hopefully the value at this point is 0!
-        const VIOLATIONS_STAGNATION_THRESHOLD: usize = 100;
-        /// usually we sample winners until we have population.len() samples, but we also stop if we've spent too long, defined here.  Is a multiple of the number of iterations before stagnation.
-        const SAMPLING_LENGTH: usize = 3;
+    /// like [Gas::cycle], but starting from the given chromosones rather than from scratch.  See [Checkpoint::warm_start].
+    #[cfg_attr(test, allow(dead_code))]
+    pub fn warm_start(
+        &self,
+        chromosones: &[S::Chromosone],
+        warm: WarmStart,
+        progress: &mut CycleProgress<S>,
+        rng: Rando,
+    ) -> Candidate<S> {
+        self.run_cycle(
+            Checkpoint::warm_start(self, chromosones, warm, rng),
+            progress,
+        )
+    }
 
-        enum State {
-            Seeding,
-            Running,
-            Stagnated,
-        }
-        let mut state = State::Seeding;
+    /// continue a [Gas::cycle] from a [Checkpoint], including the adaptive [OperatorWeights](crate::operator_weights::OperatorWeights) of [Gas::crossovers] and [Gas::mutations].
+    #[cfg_attr(test, allow(dead_code))]
+    pub fn resume(
+        &self,
+        checkpoint: Checkpoint<S>,
+        progress: &mut CycleProgress<S>,
+    ) -> Candidate<S> {
+        self.crossovers
+            .weights
+            .restore(checkpoint.crossover_stats.clone());
+        self.mutations
+            .weights
+            .restore(checkpoint.mutation_stats.clone());
+        self.run_cycle(checkpoint, progress)
+    }
 
-        // seed so on sigint it's not empty
-        winners.push(population[0].clone());
-        seed_pool.push(population[0].clone());
+    /// run a cycle from `checkpoint`.   The operator weights are shared by everything using this Gas, such as the other threads of a [Pool](crate::pool::Pool), so they are left as they are rather than restored from the checkpoint.
+    #[cfg_attr(test, allow(dead_code))]
+    pub(crate) fn run_cycle(
+        &self,
+        checkpoint: Checkpoint<S>,
+        progress: &mut CycleProgress<S>,
+    ) -> Candidate<S> {
+        self.validate();
+        let score_weights = self.fitness.weights();
+        let Checkpoint {
+            mut population,
+            mut seed_pool,
+            mut winners,
+            mut stats,
+            mut archive,
+            mut ratings,
+            mut history,
+            crossover_stats: _,
+            mutation_stats: _,
+            mut rng,
+        } = checkpoint;
+        stats.track_emas(&self.stopping.emas());
+        let start = Instant::now();
+        let elapsed = stats.elapsed;
 
-        for i in 0..(2 << 20) {
-            progress.iteration.store(i, Ordering::Relaxed);
+        loop {
+            progress.iteration.store(stats.iteration, Ordering::Relaxed);
 
-            population = self.generation(&population, &mut rng, &score_weights);
+            let (nextgen, parent_ratings, evaluated) = match &self.steady_state {
+                None => self.generation_rated(&population, &mut history, &mut rng, &score_weights),
+                Some(steady) => {
+                    steady.step(self, &population, &mut ratings, &mut rng, &score_weights)
+                }
+            };
+            let parents = std::mem::replace(&mut population, nextgen);
+            stats.iteration += 1;
+            stats.evaluations += evaluated;
+            stats.elapsed = elapsed + start.elapsed();
 
             let ts = population[0].total_score(&score_weights);
+            stats.record(ts, population[0].violations);
+            for observer in self.observers.iter() {
+                observer.observe(&stats, &parents, &parent_ratings, &winners);
+            }
             progress.score.store(ts.round() as isize, Ordering::Relaxed);
             progress
                 .violations
@@ -155,11 +301,17 @@ impl<const N: usize, const NSYMS: usize> Gas<N, NSYMS> {
                 Err(_) => (),
                 Ok(mut l) => *l = population[0].clone(),
             }
+            if archive.capacity > 0 {
+                archive.update(&population);
+                if let Ok(mut l) = progress.archive.try_write() {
+                    l.clone_from(&archive.members);
+                }
+            }
 
-            match state {
+            match stats.state {
                 State::Seeding => {
                     if population[0].violations == 0
-                        || (n_cur_violations > VIOLATIONS_STAGNATION_THRESHOLD
+                        || (self.stopping.seeded.stop(&stats)
                             && population[0].violations <= seed_pool[0].violations)
                     {
                         if population[0].violations < seed_pool[0].violations {
@@ -168,21 +320,26 @@ impl<const N: usize, const NSYMS: usize> Gas<N, NSYMS> {
                         seed_pool.push(population[0].clone());
                         if seed_pool.len() == self.population_size {
                             population = seed_pool.clone();
-                            state = State::Running;
+                            ratings.clear();
+                            history.clear();
+                            stats.state = State::Running;
                         } else {
                             population.clear();
+                            ratings.clear();
+                            history.clear();
                             for _ in 0..self.population_size {
                                 population.push(Candidate::new(self, &mut rng));
                             }
-                            cur_violations = population[0].violations;
-                            n_cur_violations = 0;
+                            stats.evaluations += population.len();
+                            stats.cur_violations = population[0].violations;
+                            stats.n_cur_violations = 0;
                         }
                     } else {
-                        if population[0].violations < cur_violations {
-                            cur_violations = population[0].violations;
-                            n_cur_violations = 1;
-                        } else if population[0].violations == cur_violations {
-                            n_cur_violations += 1;
+                        if population[0].violations < stats.cur_violations {
+                            stats.cur_violations = population[0].violations;
+                            stats.n_cur_violations = 1;
+                        } else if population[0].violations == stats.cur_violations {
+                            stats.n_cur_violations += 1;
                         }
                     }
                     progress
@@ -193,28 +350,29 @@ impl<const N: usize, const NSYMS: usize> Gas<N, NSYMS> {
                         .store(seed_pool[0].violations, Ordering::Relaxed);
                 }
                 State::Running => {
-                    if ts > best_score {
-                        best_score = ts;
+                    if ts > stats.best_score {
+                        stats.best_score = ts;
                         winners[0] = population[0].clone();
                     }
 
-                    ema99 = ema99 * EMA_FAST_CONST + ts * (1.0 - EMA_FAST_CONST);
-                    ema999 = ema999 * EMA_SLOW_CONST + ts * (1.0 - EMA_SLOW_CONST);
+                    for (constant, ema) in stats.emas.iter_mut() {
+                        *ema = *ema * *constant + ts * (1.0 - *constant);
+                    }
 
-                    if cur_violations == population[0].violations {
-                        n_cur_violations += 1;
+                    if stats.cur_violations == population[0].violations {
+                        stats.n_cur_violations += 1;
                     } else {
-                        cur_violations = population[0].violations;
-                        n_cur_violations = 1;
+                        stats.cur_violations = population[0].violations;
+                        stats.n_cur_violations = 1;
                     }
-                    if ema99 < ema999 && n_cur_violations > VIOLATIONS_STAGNATION_THRESHOLD {
-                        state = State::Stagnated;
-                        stagnation_iteration = i;
+                    if self.stopping.stagnated.stop(&stats) {
+                        stats.state = State::Stagnated;
+                        stats.stagnation_iteration = stats.iteration;
                         winners.push(population[0].clone());
                     }
                 }
                 State::Stagnated => {
-                    if ts > best_score {
+                    if ts > stats.best_score {
                         if !winners
                             .iter()
                             .any(|c| c.chromosone == population[0].chromosone)
@@ -225,27 +383,74 @@ impl<const N: usize, const NSYMS: usize> Gas<N, NSYMS> {
                             }
                         }
                     }
-                    if i > (SAMPLING_LENGTH + 1) * stagnation_iteration {
-                        break;
-                    }
+                }
+            }
 
-                    progress.progress.store(
-                        usize::max(
-                            (i - stagnation_iteration) * 100
-                                / (SAMPLING_LENGTH * stagnation_iteration),
-                            winners.len() * 100 / population.len(),
-                        ),
-                        Ordering::Relaxed,
-                    );
+            if let Some(island) = &progress.island {
+                let interval = island.migration.interval;
+                if stats.state != State::Seeding && interval > 0 && stats.iteration % interval == 0
+                {
+                    island.emigrate(&parents, &parent_ratings, &mut rng);
+                    // the immigrants start afresh, everyone else keeps their ratings
+                    for slot in island.immigrate(&mut population) {
+                        if let Some(r) = ratings.get_mut(slot) {
+                            *r = 1000;
+                        }
+                        if let Some(h) = history.get_mut(slot) {
+                            *h = Rating::default();
+                        }
+                    }
                 }
             }
 
-            if progress.sigint.load(Ordering::Relaxed) {
+            let mut percent = (self.stopping.stop.progress(&stats) * 100.0) as usize;
+            if stats.state == State::Stagnated {
+                percent = usize::max(percent, winners.len() * 100 / population.len());
+            }
+            progress
+                .progress
+                .store(usize::min(percent, 100), Ordering::Relaxed);
+
+            let sigint = progress.sigint.load(Ordering::Relaxed);
+            let interval = progress.checkpoint_interval.load(Ordering::Relaxed);
+            if sigint || (interval > 0 && stats.iteration % interval == 0) {
+                *progress.checkpoint.lock().unwrap() = Some(Checkpoint {
+                    population: population.clone(),
+                    seed_pool: seed_pool.clone(),
+                    winners: winners.clone(),
+                    stats: stats.clone(),
+                    archive: archive.clone(),
+                    ratings: ratings.clone(),
+                    history: history.clone(),
+                    crossover_stats: self.crossovers.weights.stats(),
+                    mutation_stats: self.mutations.weights.stats(),
+                    rng: rng.clone(),
+                });
+            }
+
+            if sigint || self.stopping.stop.stop(&stats) {
+                if stats.state != State::Stagnated {
+                    let mut early = vec![population[0].clone()];
+                    if stats.state == State::Seeding {
+                        early.append(&mut seed_pool);
+                    }
+                    for c in early {
+                        if !winners.iter().any(|w| w.chromosone == c.chromosone) {
+                            winners.push(c);
+                        }
+                    }
+                }
                 break;
             }
         }
 
         // tournament phase
+        for c in archive.members.iter() {
+            if !winners.iter().any(|w| w.chromosone == c.chromosone) {
+                winners.push(c.clone());
+            }
+        }
+        *progress.archive.write().unwrap() = archive.members;
         let (winner, _) = self
             .final_tournament
             .run(&winners, &mut rng, &score_weights);
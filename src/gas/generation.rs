@@ -1,5 +1,7 @@
 use super::Gas;
 use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+use crate::tournaments::elo::Rating;
 
 #[mockall_double::double]
 use crate::rando::Rando;
@@ -11,6 +13,8 @@ use crate::fitness::FitnessConfig;
 #[cfg(doc)]
 use crate::mutation::Mutation;
 #[cfg(doc)]
+use crate::operator_weights::OperatorWeights;
+#[cfg(doc)]
 use crate::tournaments::Tournament;
 
 /// Given one generation of candidates, create the next generation.   The heart of the GA.
@@ -18,39 +22,107 @@ use crate::tournaments::Tournament;
 /// Each generation consists of the following steps:
 ///
 /// 1. Run a [Tournament] to order the candidates.
-/// 2. Loop for each new child:
+/// 2. The winner and the next best rated candidates, [Gas::elitism] in all, are carried over unchanged.
+/// 3. Loop for each new child:
 /// a.  Select two parents.  Parent selection is biased by [Tournament] score and prefers selecting dissimilar parents.
 /// b.  Choose a [Crossover] algorithm to run on the two parents to create a child.
 /// c.  Choose a [Mutation] algorithm to run on the child
+/// 4. Run the fitness functions and constraints on the children, spread over [Gas::evaluation_threads] threads.
+/// 5. Record which children beat their parents in the [OperatorWeights] of the operators that created them.
 ///
 /// These arguments could be calculated inside this function rather than
 /// outside, but are taken as parameters so they don't have to be recalculated
 /// every generation:
 ///
-/// * `rng`: pass [`Rando::new()`]
+/// * `rng`: pass [`Rando::new()`], or [`Rando::seed_from_u64()`] for a reproducible generation
 /// * `score_weights`: pass [`FitnessConfig::weights()`] from [`Gas::fitness`]
+///
+/// Returns the new generation along with the ratings the [Tournament] gave the old one.
+///
+/// [Gas::observers] are not called; they are called by [Gas::cycle], which has the [CycleStats](super::cycle::CycleStats) they are given.   See [observer](crate::observer).
 
-impl<const N: usize, const NSYMS: usize> Gas<N, NSYMS> {
+impl<S: Shape> Gas<S> {
     pub fn generation(
         &self,
-        population: &Vec<Candidate<N, NSYMS>>,
+        population: &Vec<Candidate<S>>,
+        rng: &mut Rando,
+        score_weights: &Vec<f64>,
+    ) -> (Vec<Candidate<S>>, Vec<usize>) {
+        let (nextgen, ratings, _) =
+            self.generation_rated(population, &mut vec![], rng, score_weights);
+        (nextgen, ratings)
+    }
+
+    /// [Gas::generation], with ratings that persist from one generation to the next.   `history` holds the accumulated [Rating] of each member of `population`; if it doesn't match the population it starts afresh.   The tournament ratings are folded into it over [Gas::rating_memory] generations, or by [Gas::glicko] if set.   The elite are chosen by the accumulated ratings and the parents by the same ratings, or by [Glicko::weight](crate::tournaments::elo::Glicko::weight), which are the ratings returned.   On return `history` matches the new generation: the elite keep their ratings and the children start afresh.   Also returns the number of children evaluated.
+    pub fn generation_rated(
+        &self,
+        population: &Vec<Candidate<S>>,
+        history: &mut Vec<Rating>,
         rng: &mut Rando,
         score_weights: &Vec<f64>,
-    ) -> Vec<Candidate<N, NSYMS>> {
-        let mut nextgen = Vec::<Candidate<N, NSYMS>>::with_capacity(population.len());
+    ) -> (Vec<Candidate<S>>, Vec<usize>, usize) {
+        let mut nextgen = Vec::<Candidate<S>>::with_capacity(population.len());
+        // each child along with the parent it is closest to
+        let mut children =
+            Vec::<(S::Chromosone, Option<&Candidate<S>>)>::with_capacity(population.len());
 
         // tournament phase
-        let (winner, weights) = self.cycle_tournament.run(&population, rng, score_weights);
+        let (winner, tournament) = self.cycle_tournament.run(&population, rng, score_weights);
+        if history.len() != population.len() {
+            *history = vec![Rating::default(); population.len()];
+        }
+        let rated: Vec<f64> = std::iter::zip(history.iter_mut(), tournament)
+            .map(|(h, t)| match &self.glicko {
+                None => h.update(t, self.rating_memory),
+                Some(glicko) => glicko.update(h, t),
+            })
+            .collect();
+        let weights: Vec<usize> = match &self.glicko {
+            None => rated.iter().map(|r| r.round() as usize).collect(),
+            // never 0, so there's always someone to choose
+            Some(glicko) => history
+                .iter()
+                .map(|h| glicko.weight(h).round().max(1.0) as usize)
+                .collect(),
+        };
+        let mut carried = Vec::<Rating>::with_capacity(population.len());
+        carried.extend(
+            population
+                .iter()
+                .position(|c| c.chromosone == winner.chromosone)
+                .map(|w| history[w]),
+        );
         nextgen.push(winner);
+
+        // elitism phase
+        let mut order: Vec<usize> = (0..population.len()).collect();
+        order.sort_by(|a, b| rated[*b].total_cmp(&rated[*a]));
+        for i in order {
+            if nextgen.len() >= usize::min(self.elitism, population.len()) {
+                break;
+            }
+            if !nextgen
+                .iter()
+                .any(|c| c.chromosone == population[i].chromosone)
+            {
+                nextgen.push(population[i].clone());
+                carried.push(history[i]);
+            }
+        }
         let mut popdist = rng.weighted_iter(&weights);
 
         // crossover and mutation phase
-        let mut crossover_iter = self.crossovers.iter();
-        let mut mutation_iter = self.mutations.iter();
+        let mut crossover_iter = self.crossovers.iter(rng);
+        let mut mutation_iter = self.mutations.iter(rng);
 
-        // 0 was winner of tournament, already pushed to nextgen
-        for _ in 1..population.len() {
+        // the crossover, mutation and parents used for each child
+        let mut operators = Vec::with_capacity(population.len());
+
+        // the elite are already in nextgen
+        for _ in nextgen.len()..population.len() {
             let mut chromosone;
+            let mut parent;
+            let mut bred;
             loop {
                 let left = &population[popdist.next().unwrap()];
                 let mut right;
@@ -71,15 +143,81 @@ impl<const N: usize, const NSYMS: usize> Gas<N, NSYMS> {
                 let crossover = crossover_iter.next().unwrap();
                 let mutation = mutation_iter.next().unwrap();
                 chromosone = crossover.run(&left.chromosone, &right.chromosone, rng);
-                chromosone = mutation.run(&chromosone, rng);
-                if !nextgen.iter().any(|c| c.chromosone == chromosone) {
+                chromosone = match &self.domain {
+                    Some(domain) => mutation.run_within(&chromosone, domain, rng),
+                    None => mutation.run(&chromosone, rng),
+                };
+                parent = if Candidate::<S>::hamming(&chromosone, &left.chromosone)
+                    <= Candidate::<S>::hamming(&chromosone, &right.chromosone)
+                {
+                    left
+                } else {
+                    right
+                };
+                bred = (crossover_iter.index(), mutation_iter.index(), left, right);
+                if !nextgen.iter().any(|c| c.chromosone == chromosone)
+                    && !children.iter().any(|(c, _)| *c == chromosone)
+                {
                     break;
                 }
             }
-            nextgen.push(Candidate::from_chromosone(&self, chromosone));
+            children.push((chromosone, Some(parent)));
+            operators.push(bred);
+        }
+
+        // evaluation phase
+        let mut offspring = self.evaluate(&children);
+        self.record_operators(&operators, &offspring, score_weights);
+        nextgen.append(&mut offspring);
+
+        carried.resize(nextgen.len(), Rating::default());
+        *history = carried;
+        (nextgen, weights, children.len())
+    }
+
+    /// tell the [OperatorWeights] of [Gas::crossovers] and [Gas::mutations] whether each child beat both of its parents.  `operators` holds the crossover index, mutation index and the two parents of each child.
+    pub fn record_operators(
+        &self,
+        operators: &[(usize, usize, &Candidate<S>, &Candidate<S>)],
+        offspring: &[Candidate<S>],
+        score_weights: &Vec<f64>,
+    ) {
+        if self.crossovers.weights.adaptation.is_none()
+            && self.mutations.weights.adaptation.is_none()
+        {
+            return;
+        }
+        for ((crossover, mutation, left, right), child) in operators.iter().zip(offspring) {
+            let success = child.beats(left, score_weights) && child.beats(right, score_weights);
+            self.crossovers.weights.record(*crossover, success);
+            self.mutations.weights.record(*mutation, success);
         }
+    }
 
-        nextgen
+    /// run the fitness functions and constraints on each chromosone, using [Gas::evaluation_threads] threads.   If a chromosone comes with a parent, the scores are calculated incrementally with [Candidate::from_parent].   The candidates are returned in the same order as the chromosones.
+    pub fn evaluate(
+        &self,
+        chromosones: &[(S::Chromosone, Option<&Candidate<S>>)],
+    ) -> Vec<Candidate<S>> {
+        let candidate = |(chromosone, parent): &(S::Chromosone, Option<&Candidate<S>>)| match parent
+        {
+            Some(p) => Candidate::from_parent(self, chromosone.clone(), p),
+            None => Candidate::from_chromosone(self, chromosone.clone()),
+        };
+        if self.evaluation_threads <= 1 || chromosones.len() < 2 {
+            return chromosones.iter().map(candidate).collect();
+        }
+        let chunk_size = chromosones.len().div_ceil(self.evaluation_threads);
+        std::thread::scope(|s| {
+            let handles: Vec<_> = chromosones
+                .chunks(chunk_size)
+                .map(|chunk| s.spawn(move || chunk.iter().map(candidate).collect::<Vec<_>>()))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap())
+                .collect()
+        })
     }
 }
 
@@ -91,7 +229,7 @@ mod tests {
 
     #[test]
     fn test_generation() {
-        let gas = Gas::<5, 3>::dut();
+        let gas = Gas::dut();
         let mut r = Rando::default();
         r.expect_shuffle().times(1).return_const(()); // used by single_elimination_tournament
         r.expect_weighted_iter() // used by generation to select parents
@@ -110,11 +248,97 @@ mod tests {
             Candidate::from_chromosone(&gas, [0, 0, 0, 0, 0]),
             Candidate::from_chromosone(&gas, [1, 0, 1, 0, 1]),
         ];
-        let nextgen = gas.generation(&pop, &mut r, &vec![1.0; 9]);
+        let (nextgen, ratings) = gas.generation(&pop, &mut r, &vec![1.0; 9]);
+        assert_eq!(ratings, [900, 1100]);
         // second candidate has to win the tournament
         Candidate::assert_eq(
             &nextgen[0],
             &Candidate::from_chromosone(&gas, [1, 0, 1, 0, 1]),
         );
     }
+
+    #[test]
+    fn test_elitism() {
+        let mut gas = Gas::dut();
+        gas.elitism = 2;
+        let mut r = Rando::default();
+        r.expect_shuffle().times(1).return_const(()); // used by single_elimination_tournament
+        r.expect_weighted_iter() // used by generation to select parents
+            .times(1)
+            .return_const([0, 1].iter().cloned());
+
+        let pop = vec![
+            Candidate::from_chromosone(&gas, [0, 0, 0, 0, 0]),
+            Candidate::from_chromosone(&gas, [1, 0, 1, 0, 1]),
+        ];
+        let (nextgen, _) = gas.generation(&pop, &mut r, &vec![1.0; 9]);
+        // both candidates survive unchanged, the winner first
+        Candidate::assert_eq(&nextgen[0], &pop[1]);
+        Candidate::assert_eq(&nextgen[1], &pop[0]);
+    }
+
+    #[test]
+    fn test_generation_rated() {
+        let mut gas = Gas::dut();
+        gas.elitism = 2;
+        gas.rating_memory = 2;
+        let mut r = Rando::default();
+        r.expect_shuffle().times(1).return_const(()); // used by single_elimination_tournament
+        r.expect_weighted_iter() // used by generation to select parents
+            .times(1)
+            .return_const([0, 1].iter().cloned());
+
+        let pop = vec![
+            Candidate::from_chromosone(&gas, [0, 0, 0, 0, 0]),
+            Candidate::from_chromosone(&gas, [1, 0, 1, 0, 1]),
+        ];
+        // the first candidate has a good record from an earlier generation
+        let mut history = vec![
+            Rating {
+                rating: 1400.0,
+                tournaments: 1,
+                deviation: 0.0,
+            },
+            Rating {
+                rating: 600.0,
+                tournaments: 1,
+                deviation: 0.0,
+            },
+        ];
+        let (nextgen, ratings, evaluated) =
+            gas.generation_rated(&pop, &mut history, &mut r, &vec![1.0; 9]);
+        // the tournament gave 900 and 1100
+        assert_eq!(ratings, [1150, 850]);
+        Candidate::assert_eq(&nextgen[0], &pop[1]);
+        Candidate::assert_eq(&nextgen[1], &pop[0]);
+        // the history follows the candidates into the next generation
+        assert_eq!(history[0].rating, 850.0);
+        assert_eq!(history[1].rating, 1150.0);
+        assert_eq!(history[1].tournaments, 2);
+        // both were carried over, so no children were bred
+        assert_eq!(evaluated, 0);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let mut gas = Gas::dut();
+        let parent = Candidate::from_chromosone(&gas, [2, 1, 0, 0, 0]);
+        let chromosones = [
+            ([0, 0, 0, 0, 0], None),
+            ([1, 0, 1, 0, 1], None),
+            ([2, 1, 0, 1, 2], Some(&parent)),
+            ([0, 1, 2, 0, 1], None),
+            ([2, 2, 1, 1, 0], Some(&parent)),
+        ];
+        let serial = gas.evaluate(&chromosones);
+        gas.evaluation_threads = 2;
+        let parallel = gas.evaluate(&chromosones);
+        assert_eq!(serial.len(), parallel.len());
+        for ((chromosone, _), (left, right)) in
+            chromosones.iter().zip(serial.iter().zip(parallel.iter()))
+        {
+            Candidate::assert_eq(left, right);
+            Candidate::assert_eq(left, &Candidate::from_chromosone(&gas, *chromosone));
+        }
+    }
 }
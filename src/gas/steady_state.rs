@@ -0,0 +1,312 @@
+/*!
+
+Steady-state replacement, an alternative to rebuilding the whole population every [Gas::generation].
+
+Each step of a steady-state [Gas::cycle] breeds [SteadyState::children] children from the same [Gas::crossovers] and [Gas::mutations], rates each child by playing [SteadyState::games] games against random members of the population, and replaces a [Victim] if the child is rated higher.   Ratings persist between steps, so the games between candidates that haven't changed aren't rerun.   The population is kept sorted by rating, so `population[0]` is always the best rated candidate.
+
+A full [Gas::cycle_tournament] is only run when there are no ratings to carry on from: at the start of the cycle, and whenever the cycle replaces the population during seeding or migration.
+
+```
+# use gas::Gas;
+# use gas::chromosone::Fixed;
+# use gas::fitness::{self, FitnessConfig};
+# use gas::game;
+# use gas::gas::cycle::CycleProgress;
+# use gas::gas::steady_state::{SteadyState, Victim};
+# use gas::rando::Rando;
+# use gas::stopping::*;
+# use std::sync::atomic::AtomicBool;
+# use std::sync::Arc;
+// gene i is worth 1.0 at locus i, so the best chromosone is [0, 1, 2, 3]
+let desirability = (0..6).map(|g| (0..4).map(|i| if g == i { 1.0 } else { 0.0 }).collect()).collect();
+let gas = Gas {
+  // breed 2 children a step, each playing 5 games
+  steady_state: Some(SteadyState::new(2, 5, Box::new(game::Full::new()), Victim::Weakest)),
+  stopping: StoppingConfig {
+    stop: Box::new(MaxGenerations::new(500)),
+    ..StoppingConfig::default()
+  },
+  ..Gas::new(
+    Fixed::<4, 6>::new(),
+    FitnessConfig::new(vec![Box::new(fitness::LocusDesirability::new(desirability, 1.0))]),
+  )
+};
+let sigint = Arc::new(AtomicBool::new(false));
+let winner = gas.cycle(&mut CycleProgress::new(&gas, &sigint), Rando::seed_from_u64(5));
+assert_eq!(winner.chromosone, [0, 1, 2, 3]);
+```
+
+A step does much less work than a generation, so stopping criteria counted in generations, such as [ViolationsStable](crate::stopping::ViolationsStable), need correspondingly larger values.
+
+ */
+
+use super::Gas;
+use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+use crate::game::{Game, LeftRight};
+use crate::tournaments::elo::elo;
+
+#[mockall_double::double]
+use crate::rando::Rando;
+
+/// which member of the population a child replaces
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Victim {
+    /// the lowest rated member
+    Weakest,
+    /// the member with the smallest Hamming distance to the child, which preserves diversity.  Aka crowding.
+    MostSimilar,
+}
+
+/// see module documentation
+pub struct SteadyState<S: Shape> {
+    /// the number of children bred in each step
+    pub children: usize,
+    /// the number of games each child plays to establish its rating
+    pub games: usize,
+    pub game: Box<dyn Game<S> + Send + Sync>,
+    pub victim: Victim,
+    /// the K factor for the ELO rating updates
+    pub k: f64,
+}
+
+impl<S: Shape> SteadyState<S> {
+    pub fn new(
+        children: usize,
+        games: usize,
+        game: Box<dyn Game<S> + Send + Sync>,
+        victim: Victim,
+    ) -> SteadyState<S> {
+        SteadyState {
+            children,
+            games,
+            game,
+            victim,
+            k: 32.0,
+        }
+    }
+
+    /// Run one step.   `ratings` are the persistent ratings of `population`; if they don't match the population they are recalculated with [Gas::cycle_tournament].   Like [Gas::generation_rated], returns the next population along with the ratings of the old one and the number of children evaluated, and updates `ratings` to match the new population.
+    pub fn step(
+        &self,
+        gas: &Gas<S>,
+        population: &[Candidate<S>],
+        ratings: &mut Vec<usize>,
+        rng: &mut Rando,
+        score_weights: &Vec<f64>,
+    ) -> (Vec<Candidate<S>>, Vec<usize>, usize) {
+        let mut nextgen = population.to_vec();
+        if ratings.len() != population.len() {
+            let (winner, r) = gas.cycle_tournament.run(&nextgen, rng, score_weights);
+            *ratings = r;
+            // the tournament winner may be tied with others, make sure it ends up on top
+            let w = nextgen
+                .iter()
+                .position(|c| c.chromosone == winner.chromosone)
+                .unwrap();
+            ratings[w] = ratings.iter().max().copied().unwrap_or(0) + 1;
+        }
+        let parent_ratings = ratings.clone();
+
+        // breeding phase
+        let mut popdist = rng.weighted_iter(ratings);
+        // only a few children are bred per step, so start at a random point in the operator sequences rather than always at the beginning
+        let mut crossover_iter = gas.crossovers.iter(rng);
+        for _ in 0..rng.gen_range(0..gas.crossovers.weights.indices().len()) {
+            crossover_iter.next();
+        }
+        let mut mutation_iter = gas.mutations.iter(rng);
+        for _ in 0..rng.gen_range(0..gas.mutations.weights.indices().len()) {
+            mutation_iter.next();
+        }
+        let mut children =
+            Vec::<(S::Chromosone, Option<&Candidate<S>>)>::with_capacity(self.children);
+        let mut operators = Vec::with_capacity(self.children);
+        for _ in 0..self.children {
+            let left = &population[popdist.next().unwrap()];
+            let mut right = &population[popdist.next().unwrap()];
+            for _ in 0..population.len() {
+                if left != right && left.distance(right) > gas.taboo_distance {
+                    break;
+                }
+                right = &population[popdist.next().unwrap()];
+            }
+            let mut chromosone =
+                crossover_iter
+                    .next()
+                    .unwrap()
+                    .run(&left.chromosone, &right.chromosone, rng);
+            let mutation = mutation_iter.next().unwrap();
+            chromosone = match &gas.domain {
+                Some(domain) => mutation.run_within(&chromosone, domain, rng),
+                None => mutation.run(&chromosone, rng),
+            };
+            if population.iter().all(|c| c.chromosone != chromosone)
+                && children.iter().all(|(c, _)| *c != chromosone)
+            {
+                let parent = if Candidate::<S>::hamming(&chromosone, &left.chromosone)
+                    <= Candidate::<S>::hamming(&chromosone, &right.chromosone)
+                {
+                    left
+                } else {
+                    right
+                };
+                children.push((chromosone, Some(parent)));
+                operators.push((crossover_iter.index(), mutation_iter.index(), left, right));
+            }
+        }
+
+        // rating and replacement phase
+        let offspring = gas.evaluate(&children);
+        let evaluated = offspring.len();
+        gas.record_operators(&operators, &offspring, score_weights);
+        for child in offspring {
+            let mut rating = 1000.0;
+            for _ in 0..self.games {
+                let opponent = rng.gen_range(0..nextgen.len());
+                let theirs = ratings[opponent] as f64;
+                let updated = match self
+                    .game
+                    .run(&child, &nextgen[opponent], rng, score_weights)
+                {
+                    LeftRight::Left => {
+                        let (ours, theirs) = elo(self.k, rating, theirs);
+                        rating = ours;
+                        theirs
+                    }
+                    LeftRight::Right => {
+                        let (theirs, ours) = elo(self.k, theirs, rating);
+                        rating = ours;
+                        theirs
+                    }
+                };
+                ratings[opponent] = updated.round() as usize;
+            }
+            let victim = self.victim(&nextgen, ratings, &child);
+            if rating > ratings[victim] as f64 {
+                nextgen[victim] = child;
+                ratings[victim] = rating.round() as usize;
+            }
+        }
+
+        // keep the population sorted by rating so that population[0] is the best
+        let mut order: Vec<usize> = (0..nextgen.len()).collect();
+        order.sort_by(|a, b| ratings[*b].cmp(&ratings[*a]));
+        let nextgen = order.iter().map(|i| nextgen[*i].clone()).collect();
+        *ratings = order.iter().map(|i| ratings[*i]).collect();
+
+        (nextgen, parent_ratings, evaluated)
+    }
+
+    /// the index of the member to replace.   The best rated member is never chosen.
+    fn victim(
+        &self,
+        population: &[Candidate<S>],
+        ratings: &[usize],
+        child: &Candidate<S>,
+    ) -> usize {
+        let best = (0..ratings.len()).max_by_key(|i| ratings[*i]).unwrap();
+        let candidates = (0..population.len()).filter(|i| *i != best || population.len() == 1);
+        match self.victim {
+            Victim::Weakest => candidates.min_by_key(|i| ratings[*i]).unwrap(),
+            Victim::MostSimilar => candidates
+                .min_by_key(|i| population[*i].distance(child))
+                .unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fitness::{FitnessConfig, LocusDesirability};
+    use crate::game::Full;
+    use mockall::predicate;
+
+    /// one step breeding [0, 0, 0, 2, 0] from the first two members, which then beats the second
+    fn step(victim: Victim) -> (Vec<Candidate<crate::chromosone::Fixed<5, 3>>>, Vec<usize>) {
+        let mut gas = Gas::dut();
+        // the score is the number of 2's
+        gas.fitness = FitnessConfig::new(vec![Box::new(LocusDesirability::new(
+            vec![vec![0.0; 5], vec![0.0; 5], vec![1.0; 5]],
+            1.0,
+        ))]);
+        let population = vec![
+            Candidate::from_chromosone(&gas, [0, 0, 0, 0, 0]),
+            Candidate::from_chromosone(&gas, [1, 1, 1, 1, 1]),
+            Candidate::from_chromosone(&gas, [2, 2, 2, 2, 2]),
+        ];
+        let mut ratings = vec![1100, 900, 1000];
+        let mut r = Rando::default();
+        r.expect_weighted_iter()
+            .times(1)
+            .return_const([0, 1].iter().cloned());
+        // the starting points in the operator sequences
+        r.expect_gen_range()
+            .with(predicate::eq(0..1))
+            .times(2)
+            .return_const(0usize);
+        // the Null crossover copies [0, 0, 0, 0, 0], and the mutation sets locus 3 to 2
+        r.expect_gen_range()
+            .with(predicate::eq(0..5))
+            .times(1)
+            .return_const(3usize);
+        r.expect_gen_range()
+            .with(predicate::eq(0..3))
+            .times(1)
+            .return_const(2usize);
+        // the child's one game is against population[1]
+        r.expect_gen_range()
+            .with(predicate::eq(0..3))
+            .times(1)
+            .return_const(1usize);
+        let steady = SteadyState::new(1, 1, Box::new(Full::new()), victim);
+        let score_weights = gas.fitness.weights();
+        let (nextgen, parent_ratings, evaluated) =
+            steady.step(&gas, &population, &mut ratings, &mut r, &score_weights);
+        assert_eq!(parent_ratings, [1100, 900, 1000]);
+        assert_eq!(evaluated, 1);
+        (nextgen, ratings)
+    }
+
+    #[test]
+    fn test_step_weakest() {
+        let (nextgen, ratings) = step(Victim::Weakest);
+        // the child replaces the loser of its game, the lowest rated
+        let chromosones: Vec<_> = nextgen.iter().map(|c| c.chromosone).collect();
+        assert_eq!(
+            chromosones,
+            [[0, 0, 0, 0, 0], [0, 0, 0, 2, 0], [2, 2, 2, 2, 2]]
+        );
+        assert_eq!(ratings, [1100, 1012, 1000]);
+    }
+
+    #[test]
+    fn test_step_most_similar() {
+        let (nextgen, ratings) = step(Victim::MostSimilar);
+        // the child is closest to population[0], which is protected, then population[2]
+        let chromosones: Vec<_> = nextgen.iter().map(|c| c.chromosone).collect();
+        assert_eq!(
+            chromosones,
+            [[0, 0, 0, 0, 0], [0, 0, 0, 2, 0], [1, 1, 1, 1, 1]]
+        );
+        assert_eq!(ratings, [1100, 1012, 888]);
+    }
+
+    #[test]
+    fn test_victim() {
+        let gas = Gas::dut();
+        let population = vec![
+            Candidate::from_chromosone(&gas, [0, 0, 0, 0, 0]),
+            Candidate::from_chromosone(&gas, [1, 1, 1, 1, 1]),
+            Candidate::from_chromosone(&gas, [2, 2, 2, 2, 2]),
+        ];
+        let ratings = vec![1100, 900, 1000];
+        let child = Candidate::from_chromosone(&gas, [0, 0, 0, 2, 2]);
+        let mut steady = SteadyState::new(1, 1, Box::new(Full::new()), Victim::Weakest);
+        assert_eq!(steady.victim(&population, &ratings, &child), 1);
+        steady.victim = Victim::MostSimilar;
+        // closest is population[0], but the best rated is never replaced
+        assert_eq!(steady.victim(&population, &ratings, &child), 2);
+    }
+}
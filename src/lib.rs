@@ -43,7 +43,8 @@ A set of [Candidate]'s is called a population.
 Each cycle through the algorithm is called a [Gas::generation].  A generation creates a new population from an existing population.  Each generation consists of the following steps:
 
 1. Run a [Tournament] to order the candidates.
-2. Loop for each new child:
+2. Carry over the winner, and optionally the next best rated candidates (see [Gas::elitism]), unchanged.
+3. Loop for each new child:
     1.  Select two parents.  Parent selection is biased by [Tournament] score and prefers selecting dissimilar parents.
     2.  Choose a [Crossover] algorithm to run on the two parents to create a child.
     3.  Choose a [Mutation] algorithm to run on the child
@@ -52,12 +53,16 @@ Typically the GA is tuned so that Null [Crossover] and [Mutation] algorithms are
 often chosen. The Null algorithms simply clone a parent rather than performing a
 crossover or mutation.
 
-Selection of the [Crossover] and [Mutation] algorithms are not done stochastically.  Weights are configured for each algorithm, and the algorithms are cycled respecting the weights.   Therefore if the sum of the weights in [Gas::crossovers] have common factors with the sum of the weights in [Gas::mutations], some combinations of crossover and mutation may never be chosen.   In some cases you may wish to deliberately trigger this effect, but in most cases you should probably ensure that the both sum of weights do not have a common factor.
+By default selection of the [Crossover] and [Mutation] algorithms are not done stochastically.  Weights are configured for each algorithm, and the algorithms are cycled respecting the weights.   Therefore if the sum of the weights in [Gas::crossovers] have common factors with the sum of the weights in [Gas::mutations], some combinations of crossover and mutation may never be chosen.   In some cases you may wish to deliberately trigger this effect, but in most cases you should either ensure that the both sum of weights do not have a common factor, or choose the algorithms at random with [Selection::Random](operator_weights::Selection::Random).   The weights may also be left to adapt to how well each operator does, see [operator_weights].
 
-Looping the [Gas::generation] until stagnation is reached is called a [Gas::cycle].   There are three stages to each cycle: seeding, running and finalizing.   See the docs for [Gas::cycle] for more details
+Instead of replacing the whole population every generation, [Gas::steady_state] can be set to replace a few candidates at a time.   See [gas::steady_state].
+
+Looping the [Gas::generation] until stagnation is reached is called a [Gas::cycle].   There are three stages to each cycle: seeding, running and finalizing.   See the docs for [Gas::cycle] for more details.   With [Gas::archive_size] set, a cycle also keeps a Pareto [archive](gas::archive) of the best trade-offs between the scores, for when one winner isn't enough.
 
 Multiple [Gas::cycle]'s are run in parallel in a [pool]. The pool may be terminated
-early by setting the [CycleProgress::sigint] flag. When a [Gas::cycle] stops TBD.
+early by setting the [CycleProgress::sigint] flag. When a [Gas::cycle] stops early it leaves a [Checkpoint](gas::checkpoint) in [CycleProgress::checkpoint], which [Gas::resume] and [Pool::resume] can carry on from.   With the `serde` feature checkpoints can be saved to disk.   [Gas::warm_start] starts a cycle from chromosones you already have, such as a previous solution, optionally skipping seeding.
+
+All randomness comes from a [Rando] passed to [Gas::cycle] or [Pool::new].   Create it with [Rando::seed_from_u64] to make a run reproducible, or with [Rando::new] for a different run every time.
 
 # Setup and Configuration
 
@@ -65,7 +70,7 @@ There is a simple full example in examples/schedule and a minimal configuration
 
 ## Example
 
-Let's play Mastermind!   We've got 6 colors and 4 positions, so the chromosone type parameters are `<N=4, NSYMS=6>`.
+Let's play Mastermind!   We've got 6 colors and 4 positions, so the [Shape](chromosone::Shape) of the chromosone is `Fixed<4, 6>`: N=4, NSYMS=6.
 
 If we were really playing the game, our two fitness functions would be the number of white pegs and number of black pegs.   However, we'll use this example to show why it is advantageous to have a lot of fitness functions.
 
@@ -75,25 +80,26 @@ Here's our fitness function for the black pegs.   We'll use the delta between th
 
 ```
 #
-# use gas::chromosone::Gene;
+# use gas::chromosone::{Fixed, GeneType, Shape};
 #
 # use gas::fitness::{FitnessFunction, FitnessName};
 #
-pub struct Black<const N: usize, const NSYMS: usize> {
-  pub answer: [Gene; N]
+pub struct Black {
+  pub answer: Vec<usize>
 }
 
-impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for Black<N, NSYMS> {
-  fn nscores(&self) -> usize { N }
+impl<S: Shape> FitnessFunction<S> for Black {
+  fn nscores(&self) -> usize { self.answer.len() }
 
-  fn run(&self, chromosone: &[Gene; N]) -> Vec<f64> {
-    std::iter::zip(chromosone, self.answer).map(|(guess, desired)|
-      -(*guess as f64 - desired as f64).abs()
+  fn run(&self, chromosone: &S::Chromosone) -> Vec<f64> {
+    std::iter::zip(chromosone.as_ref(), &self.answer).map(|(guess, desired)|
+      -(guess.to_usize() as f64 - *desired as f64).abs()
     ).collect()
   }
 }
 
-assert_eq!(Black::<4,6>{answer:[4,3,2,1]}.run(&[4,0,2,0]), vec![0.0, -3.0, 0.0, -1.0]);
+let black: &dyn FitnessFunction<Fixed<4, 6>> = &Black{answer: vec![4,3,2,1]};
+assert_eq!(black.run(&[4,0,2,0]), vec![0.0, -3.0, 0.0, -1.0]);
 ```
 
 For the white scores, we could similarly create a [FitnessFunction] that returns 6 scores, counting each gene and returning how close each count is to the count in the answer.   But there is already a FitnessFunction that does this in the library: [fitness::ColorCount].
@@ -102,20 +108,20 @@ Once you have the chromosone and the [`FitnessFunction`] defined, you can config
 
 ```
 #
-# use gas::chromosone::Gene;
+# use gas::chromosone::{Fixed, GeneType, Shape};
 #
 # use gas::fitness::{FitnessFunction, FitnessName};
 #
-# pub struct Black<const N: usize, const NSYMS: usize> {
-# pub answer: [Gene; N]
+# pub struct Black {
+# pub answer: Vec<usize>
 # }
 #
-# impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for Black<N, NSYMS> {
-#  fn nscores(&self) -> usize { N }
+# impl<S: Shape> FitnessFunction<S> for Black {
+#  fn nscores(&self) -> usize { self.answer.len() }
 #
-#  fn run(&self, chromosone: &[Gene; N]) -> Vec<f64> {
-#    std::iter::zip(chromosone, self.answer).map(|(guess, desired)|
-#      -(*guess as f64 - desired as f64).abs()
+#  fn run(&self, chromosone: &S::Chromosone) -> Vec<f64> {
+#    std::iter::zip(chromosone.as_ref(), &self.answer).map(|(guess, desired)|
+#      -(guess.to_usize() as f64 - *desired as f64).abs()
 #    ).collect()
 #  }
 # }
@@ -127,33 +133,44 @@ Once you have the chromosone and the [`FitnessFunction`] defined, you can config
 # use gas::mutation::{MutationConfig, self};
 # use gas::tournaments;
 # use gas::crossover::{self, CrossoverConfig};
+# use gas::stopping::StoppingConfig;
 
 let gas = Gas {
+  shape: Fixed::<4, 6>::new(),
   fitness: FitnessConfig::new(vec![
-    Box::new(Black::<4, 6>{answer: [4,3,2,1]}),
-    Box::new(fitness::ColorCount::<4,6>::new(1, vec![0], vec![vec![0],vec![1],vec![1],vec![1],vec![1],vec![0]], &[""], 1.0)),
+    Box::new(Black{answer: vec![4,3,2,1]}),
+    Box::new(fitness::ColorCount::new(1, vec![0], vec![vec![0],vec![1],vec![1],vec![1],vec![1],vec![0]], &[""], 1.0)),
   ]),
   constraints: ConstraintConfig::new(vec![]),
+  domain: None,
   cycle_tournament: Box::new(tournaments::SingleElimination::new(game::Full::new())),
   final_tournament: Box::new(tournaments::FullSeason::new(game::Full::new())),
   crossovers: CrossoverConfig::new(vec![(1, Box::new(crossover::Null::new()))]),
   mutations: MutationConfig::new(vec![
     (1, Box::new(mutation::Null::new())),
-    (1, Box::new(mutation::Mutate::<4, 6>::new(1))),
-    (1, Box::new(mutation::Rotate::<4, 6>::new(1))),
+    (1, Box::new(mutation::Mutate::new(1, 6))),
+    (1, Box::new(mutation::Rotate::new(1))),
   ]),
   taboo_distance: 1,
   population_size: 10,
+  elitism: 1,
+  rating_memory: 1,
+  glicko: None,
+  archive_size: 0,
+  steady_state: None,
+  stopping: StoppingConfig::default(),
+  observers: vec![],
+  evaluation_threads: 1,
 };
 ```
 
 ## The Chromosone
 
-The chromosone is configured through const generics.   Virtually every object in the system takes two const generics as template parameters: `N` and `NSYMS`.   `N` is the length of the chromosone, and `NSYMS` is the number of genetic symbols there are.   The chromosone is defined as `[Gene; N]` where Gene is in the range `0..NSYMS`.  In chromosone.rs Gene is defined as a u8 since there are very few genetic algorithm problems where NSYMS is greater than 256.   Theoretically the `pub type Gene = u8` could be templated as well, if you need it done, feel free to ask or open a PR.
+The chromosone is configured through a [Shape](chromosone::Shape).   Virtually every object in the system is generic over the shape, which has two dimensions: `N` and `NSYMS`.   `N` is the length of the chromosone, and `NSYMS` is the number of genetic symbols there are.   The chromosone is a sequence of `N` Genes, each in the range `0..NSYMS`.   If the dimensions are known at compile time use [Fixed](chromosone::Fixed)`<N, NSYMS>`, which stores the chromosone as an array of `N` genes.   If they're only known at run time, for instance because they come from a request to a service, use [Dynamic](chromosone::Dynamic), which stores it as a `Vec`.   Both work with all the same operators, fitness functions, games and tournaments.  Each gene is stored in the shape's [GeneType](chromosone::GeneType), a u8 by default since there are very few genetic algorithm problems where NSYMS is greater than 256.   If yours is one of them, use `Fixed<N, NSYMS, u16>` or `Dynamic::<u16>::with_gene(length, nsyms)`.   A `NSYMS` that doesn't fit in the gene type is a compile error for [Fixed](chromosone::Fixed), and a panic when a [Dynamic](chromosone::Dynamic) is created.
 
 ## The `Gas` object
 
-The algorithm is configured by creating a [Gas] object.   This object specifies the fitness functions, constraints, mutation and crossover algorithms, et cetera.
+The algorithm is configured by creating a [Gas] object.   This object specifies the fitness functions, constraints, mutation and crossover algorithms, et cetera.   [Gas::new] fills in a reasonable starting point for everything but the shape and the fitness functions, so you only need to spell out the fields you want to change: `Gas { population_size: 100, ..Gas::new(shape, fitness) }`.
 
 ### Fitness Functions
 
@@ -163,7 +180,7 @@ It's quite likely that the available fitness functions will not be suitable for
 
 ### Constraints
 
-[`Constraint`]'s are much like fitness functions, except they return vectors of booleans, specifying whether the chromosone is valid or invalid.  Any candidates which have more constraint violations than others will lose any competitions.  A boolean doesn't provide much guidance to the optimizer, so providing a fitness function that will indicate if a chromosone is close to violating a constraint will be helpful.
+[`Constraint`]'s are much like fitness functions, except they return vectors of booleans, specifying whether the chromosone is valid or invalid.  Any candidates which have more constraint violations than others will lose any competitions.  A boolean doesn't provide much guidance to the optimizer, so providing a fitness function that will indicate if a chromosone is close to violating a constraint will be helpful.   When a constraint only rules out certain symbols at certain loci, a [Domain](domain::Domain) keeps the random candidates and mutations from creating those violations in the first place.
 
 ### Crossovers and Mutations
 
@@ -171,72 +188,68 @@ The set of [`Crossover`] and [`Mutation`] operators provided in the example are
 
 ### Games and Tournaments
 
-A [`Game`] replaces the simple fitness score competition in most Genetic Algorithms, so it is an interesting area for experiment.  The one used in the example worked best for us.   A [`Tournament`] is used to rank candidates.
+A [`Game`] replaces the simple fitness score competition in most Genetic Algorithms, so it is an interesting area for experiment.  The one used in the example worked best for us.   When some scores must always take precedence over others, [`game::Tiered`] compares them in priority tiers.   Continuous scores can declare [`fitness::FitnessFunction::tolerances`] so that games constructed from the fitness config, like [`game::Full::tolerant`], treat differences within the tolerance as ties rather than wins decided by floating point noise.   A [`Tournament`] is used to rank candidates.   [`Gas::rating_memory`] averages the ratings of a candidate over the generations it survives, rather than relying on a single noisy tournament, and [`Gas::glicko`] also tracks how certain each rating is.   [`Tournament::rank`] returns the whole population in order, with ties marked, for reporting or for presenting alternatives.   [`tournaments::SampledSeason`] has each candidate play a chosen number of random opponents, trading accuracy against speed.   [`tournaments::Swiss`] plays a fixed number of rounds between candidates with similar records, which gives useful ratings for a large population in far fewer games than a [`tournaments::FullSeason`].   [`tournaments::NonDominated`] skips the games altogether and ranks by Pareto dominance over all of the scores, which is much less noisy when there are a lot of them.
 
 ### Taboo Distance
 
 This ensures genetic diversity
 
-### Stagnation parameters
+### Stopping criteria
 
-There are some constants in the [`Gas::cycle`] function that are used to determine when a cycle of the algorithm has stagnated.   They perhaps should be parameterized, but for now can
+[`Gas::stopping`] decides when each phase of a [`Gas::cycle`] ends: when a seed has been found, when the population has stagnated and when to stop altogether.   [`StoppingConfig::default()`] detects stagnation with moving averages of the score and is a good choice for long runs.   To get a result within a budget, use criteria such as [`stopping::Deadline`], [`stopping::MaxGenerations`] or [`stopping::MaxEvaluations`] in [`StoppingConfig::stop`].  See [stopping] for the full list.
 
 ### nthreads and the Pool
 
-Once you have a [`Gas`] configured, you can start it via [`Gas::cycle`], or instead you can set up [`Pool`] to run several populations in parallel.  [Gas::cycle] takes a [CycleProgress] as a parameter -- you can monitor the progress of the optimizer by monitoring the [CycleProgress] from a separate thread.
+Once you have a [`Gas`] configured, you can start it via [`Gas::cycle`], or instead you can set up [`Pool`] to run several populations in parallel.  [Gas::cycle] takes a [CycleProgress] as a parameter -- you can monitor the progress of the optimizer by monitoring the [CycleProgress] from a separate thread.   [`Pool::islands`] lets the populations exchange their best candidates as they run, see [`pool::migration`].   If your fitness functions are expensive, [`Gas::evaluation_threads`] also spreads the evaluation of each generation over several threads.
 
 ```
 #
-# use gas::chromosone::Gene;
+# use gas::chromosone::{Fixed, GeneType, Shape};
 #
 # use gas::fitness::{FitnessFunction, FitnessName};
 #
-# pub struct Black<const N: usize, const NSYMS: usize> {
-# pub answer: [Gene; N]
+# pub struct Black {
+# pub answer: Vec<usize>
 # }
 #
-# impl<const N: usize, const NSYMS: usize> FitnessFunction<N, NSYMS> for Black<N, NSYMS> {
-#  fn nscores(&self) -> usize { N }
+# impl<S: Shape> FitnessFunction<S> for Black {
+#  fn nscores(&self) -> usize { self.answer.len() }
 #
-#  fn run(&self, chromosone: &[Gene; N]) -> Vec<f64> {
-#    std::iter::zip(chromosone, self.answer).map(|(guess, desired)|
-#      -(*guess as f64 - desired as f64).abs()
+#  fn run(&self, chromosone: &S::Chromosone) -> Vec<f64> {
+#    std::iter::zip(chromosone.as_ref(), &self.answer).map(|(guess, desired)|
+#      -(guess.to_usize() as f64 - *desired as f64).abs()
 #    ).collect()
 #  }
 # }
 #
 # use gas::Gas;
 # use gas::fitness::{FitnessConfig, self};
-# use gas::constraints::{ConstraintConfig, self};
-# use gas::game;
 # use gas::mutation::{MutationConfig, self};
-# use gas::tournaments;
 # use gas::crossover::{self, CrossoverConfig};
 #
 # let gas = Gas {
-#  fitness: FitnessConfig::new(vec![
-#    Box::new(Black::<4, 6>{answer: [4,3,2,1]}),
-#    Box::new(fitness::ColorCount::<4,6>::new(1, vec![0; 4], vec![vec![0],vec![1],vec![1],vec![1],vec![1],vec![0]], &[""], 1.0)),
-#  ]),
-#  constraints: ConstraintConfig::new(vec![]),
-#  cycle_tournament: Box::new(tournaments::SingleElimination::new(game::Full::new())),
-#  final_tournament: Box::new(tournaments::FullSeason::new(game::Full::new())),
 #  crossovers: CrossoverConfig::new(vec![(1, Box::new(crossover::Null::new()))]),
 #  mutations: MutationConfig::new(vec![
 #    (1, Box::new(mutation::Null::new())),
-#    (1, Box::new(mutation::Mutate::<4, 6>::new(1))),
-#    (1, Box::new(mutation::Rotate::<4, 6>::new(1))),
+#    (1, Box::new(mutation::Mutate::new(1, 6))),
+#    (1, Box::new(mutation::Rotate::new(1))),
 #  ]),
-#  taboo_distance: 1,
-#  population_size: 10,
+#  ..Gas::new(
+#    Fixed::<4, 6>::new(),
+#    FitnessConfig::new(vec![
+#      Box::new(Black{answer: vec![4,3,2,1]}),
+#      Box::new(fitness::ColorCount::new(1, vec![0; 4], vec![vec![0],vec![1],vec![1],vec![1],vec![1],vec![0]], &[""], 1.0)),
+#    ]),
+#  )
 # };
 # use std::sync::atomic::AtomicBool;
 # use std::sync::Arc;
 # use gas::gas::cycle::CycleProgress;
+# use gas::rando::Rando;
 
 let sigint = Arc::new(AtomicBool::new(false));
-let mut progress = CycleProgress::<4, 6>::new(&gas, &sigint);
-let solution = gas.cycle(&mut progress);
+let mut progress = CycleProgress::new(&gas, &sigint);
+let solution = gas.cycle(&mut progress, Rando::seed_from_u64(42));
 assert_eq!(solution.chromosone, [4,3,2,1]);
 ```
 
@@ -265,13 +278,17 @@ pub mod candidate;
 pub mod chromosone;
 pub mod constraints;
 pub mod crossover;
+pub mod domain;
 pub mod fitness;
 pub mod game;
 pub mod gas;
 pub mod helpers;
 pub mod mutation;
+pub mod observer;
+pub mod operator_weights;
 pub mod pool;
 pub mod rando;
+pub mod stopping;
 pub mod tournaments;
 
 pub use crate::gas::Gas;
@@ -293,4 +310,8 @@ use mutation::Mutation;
 #[cfg(doc)]
 use pool::Pool;
 #[cfg(doc)]
+use rando::Rando;
+#[cfg(doc)]
+use stopping::StoppingConfig;
+#[cfg(doc)]
 use tournaments::Tournament;
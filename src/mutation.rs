@@ -6,55 +6,92 @@ pub use mutate::Mutate;
 pub use null::Null;
 pub use rotate::Rotate;
 
-use crate::chromosone::Gene;
+use crate::chromosone::Shape;
+use crate::domain::Domain;
+use crate::operator_weights::{Adaptation, OperatorWeights, Selection};
 
 #[mockall_double::double]
 use crate::rando::Rando;
 
-pub trait Mutation<const N: usize, const NSYMS: usize> {
-    fn run(&self, candidate: &[Gene; N], rng: &mut Rando) -> [Gene; N];
+pub trait Mutation<S: Shape> {
+    fn run(&self, candidate: &S::Chromosone, rng: &mut Rando) -> S::Chromosone;
+    /// like [Mutation::run], but only creating genes that `domain` allows at their loci.   [Gas::generation](crate::gas::Gas::generation) calls this rather than `run` when [Gas::domain](crate::gas::Gas::domain) is set, so the domain is only given once.   The default ignores the domain, which is fine for a mutation that never changes a gene or moves one to another locus; any other mutation should implement it.
+    fn run_within(
+        &self,
+        candidate: &S::Chromosone,
+        _domain: &Domain,
+        rng: &mut Rando,
+    ) -> S::Chromosone {
+        self.run(candidate, rng)
+    }
+    /// optional: the number of symbols the mutation writes into genes, if it has its own idea of that.   [Gas::validate](crate::gas::Gas::validate) checks it against the shape.
+    fn nsyms(&self) -> Option<usize> {
+        None
+    }
 }
 
-pub struct MutationIter<'a, const N: usize, const NSYMS: usize> {
-    i: usize,
-    config: &'a MutationConfig<N, NSYMS>,
+pub struct MutationIter<'a, S: Shape> {
+    index: usize,
+    indices: Box<dyn Iterator<Item = usize>>,
+    config: &'a MutationConfig<S>,
 }
 
-impl<'a, const N: usize, const NSYMS: usize> Iterator for MutationIter<'a, N, NSYMS> {
-    type Item = &'a Box<dyn Mutation<N, NSYMS> + Sync + Send>;
+impl<'a, S: Shape> MutationIter<'a, S> {
+    /// the index into [MutationConfig::mutations_with_weights] of the operator most recently returned by `next`.   Pass it to [OperatorWeights::record].
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
 
-    fn next(&mut self) -> Option<&'a Box<dyn Mutation<N, NSYMS> + Sync + Send>> {
-        self.i += 1;
-        if self.i >= self.config.indices.len() {
-            self.i = 0;
-        }
-        Some(&self.config.mutations_with_weights[self.config.indices[self.i]].1)
+impl<'a, S: Shape> Iterator for MutationIter<'a, S> {
+    type Item = &'a Box<dyn Mutation<S> + Sync + Send>;
+
+    fn next(&mut self) -> Option<&'a Box<dyn Mutation<S> + Sync + Send>> {
+        self.index = self.indices.next()?;
+        Some(&self.config.mutations_with_weights[self.index].1)
     }
 }
 
-pub struct MutationConfig<const N: usize, const NSYMS: usize> {
-    mutations_with_weights: Vec<(usize, Box<dyn Mutation<N, NSYMS> + Sync + Send>)>,
-    indices: Vec<usize>,
+pub struct MutationConfig<S: Shape> {
+    pub mutations_with_weights: Vec<(usize, Box<dyn Mutation<S> + Sync + Send>)>,
+    pub weights: OperatorWeights,
 }
 
-impl<const N: usize, const NSYMS: usize> MutationConfig<N, NSYMS> {
+impl<S: Shape> MutationConfig<S> {
     pub fn new(
-        mutations_with_weights: Vec<(usize, Box<dyn Mutation<N, NSYMS> + Sync + Send>)>,
-    ) -> MutationConfig<N, NSYMS> {
+        mutations_with_weights: Vec<(usize, Box<dyn Mutation<S> + Sync + Send>)>,
+    ) -> MutationConfig<S> {
         let weights = mutations_with_weights
             .iter()
             .map(|c| c.0)
             .collect::<Vec<usize>>();
-        let indices = crate::helpers::multidimensional_bresenhams(&weights);
         MutationConfig {
-            indices,
+            weights: OperatorWeights::new(weights),
             mutations_with_weights,
         }
     }
 
-    pub fn iter(&self) -> MutationIter<N, NSYMS> {
+    /// like [MutationConfig::new], but the weights are only the starting point and adapt to how well each operator does.  See [operator_weights](crate::operator_weights).
+    pub fn adaptive(
+        mutations_with_weights: Vec<(usize, Box<dyn Mutation<S> + Sync + Send>)>,
+        adaptation: Adaptation,
+    ) -> MutationConfig<S> {
+        let mut config = MutationConfig::new(mutations_with_weights);
+        config.weights = OperatorWeights::adaptive(config.weights.weights(), Some(adaptation));
+        config
+    }
+
+    /// choose the operators as described by `selection`.  See [operator_weights](crate::operator_weights#selection).
+    pub fn with_selection(mut self, selection: Selection) -> MutationConfig<S> {
+        self.weights.selection = selection;
+        self
+    }
+
+    /// `rng` is only used with [Selection::Random]
+    pub fn iter(&self, rng: &mut Rando) -> MutationIter<'_, S> {
         MutationIter {
-            i: self.indices.len(),
+            index: 0,
+            indices: self.weights.sequence(rng),
             config: self,
         }
     }
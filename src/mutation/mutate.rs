@@ -1,5 +1,6 @@
 use super::Mutation;
-use crate::chromosone::Gene;
+use crate::chromosone::{GeneType, Shape};
+use crate::domain::Domain;
 
 #[mockall_double::double]
 use crate::rando::Rando;
@@ -8,31 +9,60 @@ use crate::rando::Rando;
 use mockall::*;
 
 /**
-*  This mutator randomly changes `n` locuses in the chromosone.
+*  This mutator randomly changes `n` locuses in the chromosone to one of the `nsyms` symbols, or when [Gas::domain](crate::gas::Gas::domain) is set, to one of the symbols allowed at that locus.   A locus that allows only one symbol is never changed.
 **/
-pub struct Mutate<const N: usize, const NSYMS: usize> {
+pub struct Mutate {
     pub n: usize,
+    pub nsyms: usize,
 }
 
-impl<const N: usize, const NSYMS: usize> Mutate<N, NSYMS> {
-    pub const fn new(n: usize) -> Self {
-        Mutate { n }
+impl Mutate {
+    /// `nsyms` must match the shape, see [Gas::validate](crate::gas::Gas::validate)
+    pub const fn new(n: usize, nsyms: usize) -> Self {
+        Mutate { n, nsyms }
     }
 }
 
-impl<const N: usize, const NSYMS: usize> Mutation<N, NSYMS> for Mutate<N, NSYMS> {
-    fn run(&self, chromosone: &[Gene; N], rng: &mut Rando) -> [Gene; N] {
+impl<S: Shape> Mutation<S> for Mutate {
+    fn nsyms(&self) -> Option<usize> {
+        Some(self.nsyms)
+    }
+
+    fn run(&self, chromosone: &S::Chromosone, rng: &mut Rando) -> S::Chromosone {
         let mut mutated = chromosone.clone();
         for _ in 0..self.n {
-            let pos = rng.gen_range(0..N);
-            let mut new;
-            loop {
-                new = rng.gen_range(0..NSYMS) as Gene;
-                if new != chromosone[pos] {
-                    break;
+            let pos = rng.gen_range(0..chromosone.as_ref().len());
+            let current = chromosone[pos];
+            mutated[pos] = loop {
+                let new = S::Gene::from_usize(rng.gen_range(0..self.nsyms));
+                if new != current {
+                    break new;
                 }
+            };
+        }
+        mutated
+    }
+
+    fn run_within(
+        &self,
+        chromosone: &S::Chromosone,
+        domain: &Domain,
+        rng: &mut Rando,
+    ) -> S::Chromosone {
+        let mut mutated = chromosone.clone();
+        for _ in 0..self.n {
+            let pos = rng.gen_range(0..chromosone.as_ref().len());
+            let current = chromosone[pos];
+            let symbols = domain.symbols(pos);
+            if symbols.iter().all(|s| *s == current.to_usize()) {
+                continue;
             }
-            mutated[pos] = new;
+            mutated[pos] = loop {
+                let new = S::Gene::from_usize(symbols[rng.gen_range(0..symbols.len())]);
+                if new != current {
+                    break new;
+                }
+            };
         }
         mutated
     }
@@ -41,11 +71,12 @@ impl<const N: usize, const NSYMS: usize> Mutation<N, NSYMS> for Mutate<N, NSYMS>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chromosone::Fixed;
 
     #[test]
     fn test_mutate() {
         let mut r = Rando::default();
-        let m = Mutate::<5, 3>::new(1);
+        let m: &dyn Mutation<Fixed<5, 3>> = &Mutate::new(1, 3);
         r.expect_gen_range()
             .with(predicate::eq(0..5))
             .times(1)
@@ -56,4 +87,27 @@ mod tests {
             .return_const(2usize);
         assert_eq!(m.run(&[0, 1, 2, 0, 1], &mut r), [0, 2, 2, 0, 1])
     }
+    #[test]
+    fn test_mutate_within() {
+        let mut r = Rando::default();
+        let m: &dyn Mutation<Fixed<5, 3>> = &Mutate::new(2, 3);
+        let domain = Domain::new(vec![vec![0], vec![0, 2], vec![2], vec![0], vec![1]]);
+        // locus 0 only allows the 0 it already has, so it is left alone
+        r.expect_gen_range()
+            .with(predicate::eq(0..5))
+            .times(1)
+            .return_const(0usize);
+        r.expect_gen_range()
+            .with(predicate::eq(0..5))
+            .times(1)
+            .return_const(1usize);
+        r.expect_gen_range()
+            .with(predicate::eq(0..2))
+            .times(1)
+            .return_const(1usize);
+        assert_eq!(
+            m.run_within(&[0, 1, 2, 0, 1], &domain, &mut r),
+            [0, 2, 2, 0, 1]
+        )
+    }
 }
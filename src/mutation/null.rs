@@ -1,5 +1,5 @@
 use super::Mutation;
-use crate::chromosone::Gene;
+use crate::chromosone::Shape;
 
 #[mockall_double::double]
 use crate::rando::Rando;
@@ -7,16 +7,16 @@ use crate::rando::Rando;
 /**
 *  This mutator randomly changes `n` locuses in the chromosone.
 **/
-pub struct Null<const N: usize, const NSYMS: usize> {}
+pub struct Null {}
 
-impl<const N: usize, const NSYMS: usize> Null<N, NSYMS> {
-    pub const fn new() -> Null<N, NSYMS> {
+impl Null {
+    pub const fn new() -> Null {
         Null {}
     }
 }
 
-impl<const N: usize, const NSYMS: usize> Mutation<N, NSYMS> for Null<N, NSYMS> {
-    fn run(&self, chromosone: &[Gene; N], _rng: &mut Rando) -> [Gene; N] {
+impl<S: Shape> Mutation<S> for Null {
+    fn run(&self, chromosone: &S::Chromosone, _rng: &mut Rando) -> S::Chromosone {
         chromosone.clone()
     }
 }
@@ -1,5 +1,6 @@
 use super::Mutation;
-use crate::chromosone::Gene;
+use crate::chromosone::{GeneType, Shape};
+use crate::domain::Domain;
 
 #[cfg(test)]
 use mockall::*;
@@ -7,42 +8,72 @@ use mockall::*;
 #[mockall_double::double]
 use crate::rando::Rando;
 /**
-*   If `n` is 1 then this mutator swaps the genes at 2 locuses.   If `n` is 2 or more, then this mutator chooses n+1 locuses, and rotates the genes through those positions.
+*   If `n` is 1 then this mutator swaps the genes at 2 locuses.   If `n` is 2 or more, then this mutator chooses n+1 locuses, and rotates the genes through those positions.   When [Gas::domain](crate::gas::Gas::domain) is set the locuses are only chosen so that every gene moves to a locus that allows it; if there's no such rotation starting from the first locus chosen, the chromosone is returned unchanged.
 **/
-pub struct Rotate<const N: usize, const NSYMS: usize> {
+pub struct Rotate {
     pub n: usize,
 }
 
-impl<const N: usize, const NSYMS: usize> Rotate<N, NSYMS> {
-    pub const fn new(n: usize) -> Rotate<N, NSYMS> {
+impl Rotate {
+    pub const fn new(n: usize) -> Rotate {
         Rotate { n }
     }
 }
 
-impl<const N: usize, const NSYMS: usize> Mutation<N, NSYMS> for Rotate<N, NSYMS> {
-    fn run(&self, chromosone: &[Gene; N], rng: &mut Rando) -> [Gene; N] {
+impl<S: Shape> Mutation<S> for Rotate {
+    fn run(&self, chromosone: &S::Chromosone, rng: &mut Rando) -> S::Chromosone {
         let mut mutated = chromosone.clone();
-        let mut curpos = rng.gen_range(0..chromosone.len());
+        let mut curpos = rng.gen_range(0..chromosone.as_ref().len());
         let origval = chromosone[curpos];
         let mut nextpos = curpos;
         for _ in 0..self.n {
-            nextpos = rng.gen_range(0..chromosone.len());
+            nextpos = rng.gen_range(0..chromosone.as_ref().len());
             mutated[curpos] = chromosone[nextpos];
             curpos = nextpos;
         }
         mutated[nextpos] = origval;
         mutated
     }
+
+    fn run_within(
+        &self,
+        chromosone: &S::Chromosone,
+        domain: &Domain,
+        rng: &mut Rando,
+    ) -> S::Chromosone {
+        let len = chromosone.as_ref().len();
+        let mut mutated = chromosone.clone();
+        let mut curpos = rng.gen_range(0..len);
+        let origval = chromosone[curpos];
+        for step in 0..self.n {
+            let last = step + 1 == self.n;
+            let fits: Vec<usize> = (0..len)
+                .filter(|p| {
+                    domain.allows(curpos, chromosone[*p].to_usize())
+                        && (!last || domain.allows(*p, origval.to_usize()))
+                })
+                .collect();
+            if fits.is_empty() {
+                return chromosone.clone();
+            }
+            let nextpos = fits[rng.gen_range(0..fits.len())];
+            mutated[curpos] = chromosone[nextpos];
+            curpos = nextpos;
+        }
+        mutated[curpos] = origval;
+        mutated
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chromosone::Fixed;
 
     #[test]
     fn test_rotate1() {
         let mut r = Rando::default();
-        let m = Rotate::<5, 3>::new(1);
+        let m: &dyn Mutation<Fixed<5, 3>> = &Rotate::new(1);
         r.expect_gen_range()
             .with(predicate::eq(0..5))
             .times(1)
@@ -57,7 +88,7 @@ mod tests {
     #[test]
     fn test_rotate2() {
         let mut r = Rando::default();
-        let m = Rotate::<5, 3>::new(2);
+        let m: &dyn Mutation<Fixed<5, 3>> = &Rotate::new(2);
         r.expect_gen_range()
             .with(predicate::eq(0..5))
             .times(1)
@@ -72,4 +103,30 @@ mod tests {
             .return_const(2usize);
         assert_eq!(m.run(&[0, 1, 2, 0, 1], &mut r), [1, 2, 0, 0, 1]);
     }
+    #[test]
+    fn test_rotate_within() {
+        let mut r = Rando::default();
+        // 0 can go anywhere, but 1 and 2 only where they already are or at locus 3
+        let m: &dyn Mutation<Fixed<5, 3>> = &Rotate::new(1);
+        let domain = Domain::new(vec![
+            vec![0],
+            vec![0, 1],
+            vec![0, 2],
+            vec![0, 1, 2],
+            vec![0],
+        ]);
+        r.expect_gen_range()
+            .with(predicate::eq(0..5))
+            .times(1)
+            .return_const(1usize);
+        // the 1 at locus 1 can only stay put or be swapped with the 0 at locus 3
+        r.expect_gen_range()
+            .with(predicate::eq(0..2))
+            .times(1)
+            .return_const(1usize);
+        assert_eq!(
+            m.run_within(&[0, 1, 2, 0, 0], &domain, &mut r),
+            [0, 0, 2, 1, 0]
+        );
+    }
 }
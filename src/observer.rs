@@ -0,0 +1,82 @@
+/*!
+
+Observers are called by [Gas::cycle] after every generation.   They see everything the cycle sees, so they can be used to log diversity, plot convergence or signal a restart through [CycleProgress::sigint].
+
+Observers are registered in [Gas::observers].   They are only called from [Gas::cycle], not from [Gas::generation]: an observer is handed the [CycleStats] and the winners sampled so far, which only exist inside a cycle, and a caller that drives [Gas::generation] itself already holds the population and the ratings it returns.   Since a [Gas] is shared between the threads of a [Pool](crate::pool::Pool), an observer that records data must use interior mutability.
+
+Closures with the same signature as [GenerationObserver::observe] are observers:
+
+```
+# use gas::Gas;
+# use gas::chromosone::Fixed;
+# use gas::candidate::Candidate;
+# use gas::fitness::{self, FitnessConfig};
+# use gas::gas::cycle::{CycleProgress, CycleStats};
+# use gas::rando::Rando;
+# use gas::stopping::*;
+# use std::sync::atomic::AtomicBool;
+# use std::sync::{Arc, Mutex};
+// the iteration, population size and number of ratings of each generation
+let seen = Arc::new(Mutex::new(Vec::<(usize, usize, usize)>::new()));
+let log = seen.clone();
+let gas = Gas {
+  stopping: StoppingConfig {
+    stop: Box::new(MaxGenerations::new(20)),
+    ..StoppingConfig::default()
+  },
+  observers: vec![Box::new(
+    move |stats: &CycleStats, population: &[Candidate<Fixed<4, 6>>], ratings: &[usize], _winners: &[Candidate<Fixed<4, 6>>]| {
+      log.lock().unwrap().push((stats.iteration, population.len(), ratings.len()));
+    },
+  )],
+  ..Gas::new(
+    Fixed::<4, 6>::new(),
+    FitnessConfig::new(vec![
+      Box::new(fitness::LocusDesirability::new(vec![vec![1.0, 0.0, 0.0, 2.0]; 6], 1.0)),
+    ]),
+  )
+};
+let sigint = Arc::new(AtomicBool::new(false));
+gas.cycle(&mut CycleProgress::new(&gas, &sigint), Rando::seed_from_u64(1));
+// called once per generation, in order, with the whole rated population
+let seen = seen.lock().unwrap();
+assert_eq!(seen.iter().map(|s| s.0).collect::<Vec<_>>(), (1..=20).collect::<Vec<_>>());
+assert!(seen.iter().all(|s| s.1 == 10 && s.2 == 10));
+```
+
+ */
+
+use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+use crate::gas::cycle::CycleStats;
+
+#[cfg(doc)]
+use crate::gas::{cycle::CycleProgress, Gas};
+
+pub trait GenerationObserver<S: Shape> {
+    /// called by [Gas::cycle] after every generation.
+    ///
+    /// `stats` have been updated for the generation just created, but `stats.state` is still the phase the generation was created in.   `population` is the population that the tournament rated, aka the parents of the generation just created, and `ratings` are their tournament ratings in the same order.   `winners` are the candidates sampled for the final tournament so far.
+    fn observe(
+        &self,
+        stats: &CycleStats,
+        population: &[Candidate<S>],
+        ratings: &[usize],
+        winners: &[Candidate<S>],
+    );
+}
+
+impl<F, S: Shape> GenerationObserver<S> for F
+where
+    F: Fn(&CycleStats, &[Candidate<S>], &[usize], &[Candidate<S>]),
+{
+    fn observe(
+        &self,
+        stats: &CycleStats,
+        population: &[Candidate<S>],
+        ratings: &[usize],
+        winners: &[Candidate<S>],
+    ) {
+        self(stats, population, ratings, winners)
+    }
+}
@@ -0,0 +1,308 @@
+/*!
+
+The weights of the operators in a [CrossoverConfig] or a [MutationConfig].
+
+By default the weights are fixed when the config is created.   With an [Adaptation] they are recalculated as the cycle runs: every time a child is created the engine records whether it beat both of its parents (see [Candidate::beats]), and each operator's weight is set in proportion to how often the children it created succeeded.   This replaces hand tuning the weights with parameter sweeps.
+
+```
+# use gas::Gas;
+# use gas::chromosone::Fixed;
+# use gas::fitness::{self, FitnessConfig};
+# use gas::gas::cycle::CycleProgress;
+# use gas::mutation::{self, MutationConfig};
+# use gas::operator_weights::Adaptation;
+# use gas::crossover::{self, CrossoverConfig};
+# use gas::rando::Rando;
+# use gas::stopping::*;
+# use std::sync::atomic::AtomicBool;
+# use std::sync::Arc;
+let desirability = (0..6).map(|g| (0..4).map(|i| if g == i { 1.0 } else { 0.0 }).collect()).collect();
+let gas = Gas {
+  mutations: MutationConfig::adaptive(
+    vec![
+      (1, Box::new(mutation::Null::new())),
+      (1, Box::new(mutation::Mutate::new(1, 6))),
+    ],
+    // recalculate every 100 children
+    Adaptation::new(100, 20, 1, 0.5),
+  ),
+#  crossovers: CrossoverConfig::new(vec![(1, Box::new(crossover::Null::new()))]),
+#  stopping: StoppingConfig {
+#    stop: Box::new(MaxGenerations::new(100)),
+#    ..StoppingConfig::default()
+#  },
+  ..Gas::new(
+    Fixed::<4, 6>::new(),
+    FitnessConfig::new(vec![Box::new(fitness::LocusDesirability::new(desirability, 1.0))]),
+  )
+};
+let sigint = Arc::new(AtomicBool::new(false));
+gas.cycle(&mut CycleProgress::new(&gas, &sigint), Rando::seed_from_u64(1));
+// a Null crossover followed by a Null mutation just copies a parent, which never beats it
+let weights = gas.mutations.weights.weights();
+assert!(weights[0] < weights[1]);
+```
+
+The statistics are shared by everything that uses the config, so the threads of a [Pool] pool their evidence.   This also means that a seeded [Pool] with adaptive weights is not reproducible, although a single [Gas::cycle] still is.
+
+## Selection
+
+By default the operators are [Selection::Cycled]: each config steps through a fixed sequence that respects the weights.   Because [Gas::crossovers] and [Gas::mutations] are stepped together, if the lengths of their two sequences share a common factor some pairings of crossover and mutation never happen.   With [Selection::Random] each operator is instead drawn from the [Rando] in proportion to its weight, so every pairing gets tried and there's no need to think about the sums of the weights.
+
+```
+# use gas::chromosone::Fixed;
+# use gas::crossover::{self, CrossoverConfig};
+# use gas::mutation::{self, MutationConfig};
+# use gas::operator_weights::Selection;
+# use gas::rando::Rando;
+# use std::collections::HashSet;
+fn pairings(selection: Selection) -> usize {
+  let crossovers = CrossoverConfig::<Fixed<4, 6>>::new(vec![
+    (1, Box::new(crossover::Null::new())),
+    (1, Box::new(crossover::Splice::new())),
+  ])
+  .with_selection(selection);
+  let mutations = MutationConfig::<Fixed<4, 6>>::new(vec![
+    (1, Box::new(mutation::Null::new())),
+    (1, Box::new(mutation::Mutate::new(1, 6))),
+  ])
+  .with_selection(selection);
+  let mut rng = Rando::seed_from_u64(1);
+  let mut crossover_iter = crossovers.iter(&mut rng);
+  let mut mutation_iter = mutations.iter(&mut rng);
+  let mut pairs = HashSet::new();
+  for _ in 0..100 {
+    crossover_iter.next();
+    mutation_iter.next();
+    pairs.insert((crossover_iter.index(), mutation_iter.index()));
+  }
+  pairs.len()
+}
+// both sequences have length 2, so they stay in lockstep
+assert_eq!(pairings(Selection::Cycled), 2);
+assert_eq!(pairings(Selection::Random), 4);
+```
+
+ */
+
+use crate::helpers::multidimensional_bresenhams;
+use std::sync::Mutex;
+
+#[mockall_double::double]
+use crate::rando::Rando;
+
+#[cfg(doc)]
+use crate::{
+    candidate::Candidate, crossover::CrossoverConfig, gas::checkpoint::Checkpoint, gas::Gas,
+    mutation::MutationConfig, pool::Pool,
+};
+
+/// see module documentation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Adaptation {
+    /// the weights are recalculated after this many children have been recorded
+    pub interval: usize,
+    /// the recalculated weights add up to roughly this.   Larger values give finer control at the cost of a longer sequence.
+    pub resolution: usize,
+    /// the smallest weight an operator can be given, so that every operator keeps getting tried.  At least 1.
+    pub floor: usize,
+    /// the success and trial counts are multiplied by this at every recalculation, so that recent results count for more.   1.0 never forgets.
+    pub decay: f64,
+}
+
+impl Adaptation {
+    pub const fn new(interval: usize, resolution: usize, floor: usize, decay: f64) -> Adaptation {
+        Adaptation {
+            interval,
+            resolution,
+            floor,
+            decay,
+        }
+    }
+}
+
+impl Default for Adaptation {
+    /// recalculate every 1000 children, with weights summing to about 100, none below 1, and halving the influence of old results every recalculation.
+    fn default() -> Self {
+        Adaptation::new(1000, 100, 1, 0.5)
+    }
+}
+
+/// how the next operator is chosen
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Selection {
+    /// step through [multidimensional_bresenhams] of the weights.   Evenly spread and uses no random numbers, but see the module documentation.
+    #[default]
+    Cycled,
+    /// draw each operator at random in proportion to its weight
+    Random,
+}
+
+/// the current weights and the results recorded so far.   Saved in a [Checkpoint] so that a resumed cycle carries on adapting from where it left off.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OperatorStats {
+    pub weights: Vec<usize>,
+    /// [multidimensional_bresenhams] of `weights`
+    pub indices: Vec<usize>,
+    pub successes: Vec<f64>,
+    pub trials: Vec<f64>,
+    /// the number of children recorded
+    pub recorded: usize,
+}
+
+pub struct OperatorWeights {
+    pub adaptation: Option<Adaptation>,
+    pub selection: Selection,
+    stats: Mutex<OperatorStats>,
+}
+
+impl OperatorWeights {
+    /// fixed weights
+    pub fn new(weights: Vec<usize>) -> OperatorWeights {
+        OperatorWeights::adaptive(weights, None)
+    }
+
+    /// `weights` are the starting weights, adjusted as results are recorded if `adaptation` is set.
+    pub fn adaptive(weights: Vec<usize>, adaptation: Option<Adaptation>) -> OperatorWeights {
+        let n = weights.len();
+        OperatorWeights {
+            adaptation,
+            selection: Selection::Cycled,
+            stats: Mutex::new(OperatorStats {
+                indices: multidimensional_bresenhams(&weights),
+                weights,
+                successes: vec![0.0; n],
+                trials: vec![0.0; n],
+                recorded: 0,
+            }),
+        }
+    }
+
+    /// the current weights
+    pub fn weights(&self) -> Vec<usize> {
+        self.stats.lock().unwrap().weights.clone()
+    }
+
+    /// a copy of the adaptation state
+    pub fn stats(&self) -> OperatorStats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// carry on from the state saved by [OperatorWeights::stats]
+    pub fn restore(&self, stats: OperatorStats) {
+        assert_eq!(
+            stats.weights.len(),
+            self.stats.lock().unwrap().weights.len()
+        );
+        *self.stats.lock().unwrap() = stats;
+    }
+
+    /// the sequence of operator indices to cycle through for the current weights.  See [multidimensional_bresenhams].
+    pub fn indices(&self) -> Vec<usize> {
+        self.stats.lock().unwrap().indices.clone()
+    }
+
+    /// an endless sequence of operator indices for the current weights, chosen according to [OperatorWeights::selection].   [Selection::Cycled] doesn't touch `rng`.
+    pub fn sequence(&self, rng: &mut Rando) -> Box<dyn Iterator<Item = usize>> {
+        match self.selection {
+            Selection::Cycled => Box::new(self.indices().into_iter().cycle()),
+            Selection::Random => Box::new(rng.weighted_iter(&self.weights())),
+        }
+    }
+
+    /// record whether a child created by `operator` was a success.   Does nothing unless adaptive.
+    pub fn record(&self, operator: usize, success: bool) {
+        let adaptation = match self.adaptation {
+            Some(a) => a,
+            None => return,
+        };
+        let mut stats = self.stats.lock().unwrap();
+        stats.trials[operator] += 1.0;
+        if success {
+            stats.successes[operator] += 1.0;
+        }
+        stats.recorded += 1;
+        if stats.recorded.is_multiple_of(adaptation.interval) {
+            // Laplace smoothing so that an operator with no trials gets a middling rate
+            let rates: Vec<f64> = (0..stats.weights.len())
+                .map(|i| (stats.successes[i] + 1.0) / (stats.trials[i] + 2.0))
+                .collect();
+            let total: f64 = rates.iter().sum();
+            stats.weights = rates
+                .iter()
+                .map(|r| {
+                    usize::max(
+                        adaptation.floor.max(1),
+                        (r / total * adaptation.resolution as f64).round() as usize,
+                    )
+                })
+                .collect();
+            stats.indices = multidimensional_bresenhams(&stats.weights);
+            for i in 0..stats.weights.len() {
+                stats.successes[i] *= adaptation.decay;
+                stats.trials[i] *= adaptation.decay;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed() {
+        let w = OperatorWeights::new(vec![2, 3, 1]);
+        w.record(0, true);
+        assert_eq!(w.weights(), vec![2, 3, 1]);
+        assert_eq!(w.indices(), vec![0, 1, 2, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_adaptive() {
+        let w = OperatorWeights::adaptive(vec![1, 1, 1], Some(Adaptation::new(10, 20, 1, 1.0)));
+        for _ in 0..5 {
+            w.record(0, true);
+            w.record(1, false);
+        }
+        // rates are 6/7, 1/7 and 1/2
+        assert_eq!(w.weights(), vec![11, 2, 7]);
+        assert_eq!(w.indices().len(), 20);
+    }
+
+    #[test]
+    fn test_restore() {
+        let adaptation = Some(Adaptation::new(10, 20, 1, 1.0));
+        let w = OperatorWeights::adaptive(vec![1, 1, 1], adaptation);
+        for _ in 0..4 {
+            w.record(0, true);
+            w.record(1, false);
+        }
+        // a fresh copy carries on where the first left off
+        let resumed = OperatorWeights::adaptive(vec![1, 1, 1], adaptation);
+        resumed.restore(w.stats());
+        for o in [&w, &resumed] {
+            o.record(0, true);
+            o.record(1, false);
+        }
+        assert_eq!(resumed.stats(), w.stats());
+        assert_eq!(resumed.weights(), vec![11, 2, 7]);
+    }
+
+    #[test]
+    fn test_sequence() {
+        let mut w = OperatorWeights::new(vec![2, 1]);
+        let mut r = Rando::default();
+        let cycled: Vec<usize> = w.sequence(&mut r).take(5).collect();
+        assert_eq!(cycled, vec![0, 1, 0, 0, 1]);
+
+        w.selection = Selection::Random;
+        r.expect_weighted_iter()
+            .withf(|weights| weights == [2, 1])
+            .times(1)
+            .return_const([1, 1, 0].iter().cloned());
+        let random: Vec<usize> = w.sequence(&mut r).collect();
+        assert_eq!(random, vec![1, 1, 0]);
+    }
+}
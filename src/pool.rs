@@ -1,6 +1,12 @@
+pub mod migration;
+
 use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+use crate::gas::archive::Archive;
+use crate::gas::checkpoint::Checkpoint;
 use crate::gas::cycle::CycleProgress;
 use crate::gas::Gas;
+use migration::{Island, Migration};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::thread;
@@ -12,33 +18,98 @@ use crate::rando::Rando;
 *
 *  Set sigint to true to stop the iterations manually.
 *
+*  Each thread is given a [Rando::fork] of `rng`, so a pool created with [Rando::seed_from_u64] is reproducible as long as it is not stopped early.
+*
+*  [Pool::islands] creates a pool whose threads exchange candidates as they run.  See [migration].
+*
+*  [Pool::archive] collects the Pareto archives of the threads.
+*
+*  When sigint is set each thread leaves a [Checkpoint] in its [CycleProgress::checkpoint].   Pass those to [Pool::resume] to carry on.
+*
 **/
-pub struct Pool<const N: usize, const NSYMS: usize> {
-    pub progresses: Vec<CycleProgress<N, NSYMS>>,
-    pub handles: Vec<thread::JoinHandle<Candidate<N, NSYMS>>>,
+pub struct Pool<S: Shape> {
+    pub progresses: Vec<CycleProgress<S>>,
+    pub handles: Vec<thread::JoinHandle<Candidate<S>>>,
     pub sigint: Arc<AtomicBool>,
+    rng: Rando,
 }
 
-impl<const N: usize, const NSYMS: usize> Pool<N, NSYMS> {
+impl<S: Shape> Pool<S> {
     pub fn new(
-        gas: Arc<Gas<N, NSYMS>>,
+        gas: Arc<Gas<S>>,
         nthreads: usize,
         sigint: Arc<AtomicBool>,
-    ) -> Pool<N, NSYMS> {
-        let mut progresses = Vec::<CycleProgress<N, NSYMS>>::with_capacity(nthreads);
-        let mut handles = Vec::<thread::JoinHandle<Candidate<N, NSYMS>>>::with_capacity(nthreads);
+        mut rng: Rando,
+    ) -> Pool<S> {
+        let checkpoints = (0..nthreads)
+            .map(|_| Checkpoint::new(&gas, rng.fork()))
+            .collect();
+        Pool::spawn(gas, checkpoints, sigint, rng, None)
+    }
+
+    /// like [Pool::new], but the threads are islands that periodically exchange their best candidates.
+    pub fn islands(
+        gas: Arc<Gas<S>>,
+        nthreads: usize,
+        sigint: Arc<AtomicBool>,
+        mut rng: Rando,
+        migration: Migration,
+    ) -> Pool<S> {
+        let checkpoints = (0..nthreads)
+            .map(|_| Checkpoint::new(&gas, rng.fork()))
+            .collect();
+        Pool::spawn(gas, checkpoints, sigint, rng, Some(migration))
+    }
 
-        for _ in 0..nthreads {
+    /// continue a pool from the [Checkpoint]'s left by its threads, one thread per checkpoint.   `rng` is only used by [Pool::winner].   Pass a `migration` to continue as [Pool::islands].   The threads share the adaptive operator weights of `gas`, which are restored from the first checkpoint.
+    pub fn resume(
+        gas: Arc<Gas<S>>,
+        checkpoints: Vec<Checkpoint<S>>,
+        sigint: Arc<AtomicBool>,
+        rng: Rando,
+        migration: Option<Migration>,
+    ) -> Pool<S> {
+        if let Some(checkpoint) = checkpoints.first() {
+            gas.crossovers
+                .weights
+                .restore(checkpoint.crossover_stats.clone());
+            gas.mutations
+                .weights
+                .restore(checkpoint.mutation_stats.clone());
+        }
+        Pool::spawn(gas, checkpoints, sigint, rng, migration)
+    }
+
+    /// start a thread running each checkpoint
+    fn spawn(
+        gas: Arc<Gas<S>>,
+        checkpoints: Vec<Checkpoint<S>>,
+        sigint: Arc<AtomicBool>,
+        rng: Rando,
+        migration: Option<Migration>,
+    ) -> Pool<S> {
+        let mut islands = migration
+            .map(|m| Island::archipelago(checkpoints.len(), m))
+            .unwrap_or_default()
+            .into_iter();
+        let mut progresses = Vec::<CycleProgress<S>>::with_capacity(checkpoints.len());
+        let mut handles = Vec::<thread::JoinHandle<Candidate<S>>>::with_capacity(checkpoints.len());
+
+        for checkpoint in checkpoints {
             let igas = gas.clone();
             let mut progress = CycleProgress::new(&igas, &sigint);
+            progress.island = islands.next();
             progresses.push(progress.clone());
 
-            handles.push(thread::spawn(move || igas.cycle(&mut progress)));
+            handles.push(thread::spawn(move || {
+                igas.run_cycle(checkpoint, &mut progress)
+            }));
         }
         Pool {
             progresses,
             handles,
             sigint,
+            rng,
         }
     }
 
@@ -46,13 +117,21 @@ impl<const N: usize, const NSYMS: usize> Pool<N, NSYMS> {
         self.handles.iter().all(|h| h.is_finished())
     }
 
-    pub fn winner(&mut self, gas: Arc<Gas<N, NSYMS>>) -> Candidate<N, NSYMS> {
-        let mut rng = Rando::new();
-        let winners: Vec<Candidate<N, NSYMS>> =
+    /// the Pareto archives of all the threads merged into one of at most [Gas::archive_size] members.   Can be called while the pool is running.   See [archive](crate::gas::archive).
+    pub fn archive(&self, gas: &Gas<S>) -> Vec<Candidate<S>> {
+        let mut archive = Archive::new(gas.archive_size);
+        for progress in self.progresses.iter() {
+            archive.update(&progress.archive.read().unwrap());
+        }
+        archive.members
+    }
+
+    pub fn winner(&mut self, gas: Arc<Gas<S>>) -> Candidate<S> {
+        let winners: Vec<Candidate<S>> =
             self.handles.drain(..).map(|h| h.join().unwrap()).collect();
         let (winner, _) = gas
             .final_tournament
-            .run(&winners, &mut rng, &gas.fitness.weights());
+            .run(&winners, &mut self.rng, &gas.fitness.weights());
         winner
     }
 }
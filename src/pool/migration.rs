@@ -0,0 +1,217 @@
+/*!
+
+The island model.   Normally the threads of a [Pool] run completely independent [Gas::cycle]'s and only meet in the final tournament.   With a [Migration] configured via [Pool::islands], each thread is an island that periodically sends copies of its best candidates to its neighbours and takes in the candidates its neighbours sent it.
+
+Migration only happens in [State::Running] and [State::Stagnated]: while [State::Seeding] the population is restarted from scratch for every seed, so immigrants would just be thrown away.
+
+```
+# use gas::Gas;
+# use gas::chromosone::Fixed;
+# use gas::fitness::{self, FitnessConfig};
+# use gas::pool::Pool;
+# use gas::pool::migration::{Migration, Topology};
+# use gas::rando::Rando;
+# use gas::stopping::*;
+# use std::sync::atomic::AtomicBool;
+# use std::sync::Arc;
+# let gas = Arc::new(Gas {
+#   stopping: StoppingConfig {
+#     stop: Box::new(MaxGenerations::new(200)),
+#     ..StoppingConfig::default()
+#   },
+#   ..Gas::new(
+#     Fixed::<4, 6>::new(),
+#     FitnessConfig::new(vec![
+#       Box::new(fitness::LocusDesirability::new(vec![vec![1.0, 0.0, 0.0, 2.0]; 6], 1.0)),
+#     ]),
+#   )
+# });
+let sigint = Arc::new(AtomicBool::new(false));
+// every 10 generations, send the best 2 candidates to the next thread
+let migration = Migration::new(Topology::Ring, 10, 2);
+let mut pool = Pool::islands(gas.clone(), 4, sigint, Rando::seed_from_u64(1), migration);
+let winner = pool.winner(gas);
+```
+
+The threads of a pool run at different speeds, so the order in which immigrants arrive varies between runs.   A pool with migration is therefore not reproducible even when it is seeded.
+
+ */
+
+use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+#[mockall_double::double]
+use crate::rando::Rando;
+use std::sync::{Arc, Mutex};
+
+#[cfg(doc)]
+use crate::gas::{cycle::State, Gas};
+#[cfg(doc)]
+use crate::pool::Pool;
+
+/// which islands an island sends its emigrants to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Topology {
+    /// island `i` sends to island `i + 1`, and the last island sends to the first
+    Ring,
+    /// every island sends to every other island
+    FullyConnected,
+    /// each migration goes to a randomly chosen other island
+    Random,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Migration {
+    pub topology: Topology,
+    /// migrate every this many generations.   0 never migrates.
+    pub interval: usize,
+    /// the number of top rated candidates sent in each migration
+    pub count: usize,
+}
+
+impl Migration {
+    /// an `interval` of 0 never migrates
+    pub const fn new(topology: Topology, interval: usize, count: usize) -> Migration {
+        Migration {
+            topology,
+            interval,
+            count,
+        }
+    }
+}
+
+/// One island of an archipelago.   Passed to a [Gas::cycle] through [CycleProgress::island](crate::gas::cycle::CycleProgress::island).
+#[derive(Clone)]
+pub struct Island<S: Shape> {
+    pub index: usize,
+    pub migration: Migration,
+    /// candidates waiting to arrive on each island
+    inboxes: Arc<Vec<Mutex<Vec<Candidate<S>>>>>,
+}
+
+impl<S: Shape> Island<S> {
+    /// `n` islands that migrate to each other
+    pub fn archipelago(n: usize, migration: Migration) -> Vec<Island<S>> {
+        let inboxes = Arc::new((0..n).map(|_| Mutex::new(vec![])).collect::<Vec<_>>());
+        (0..n)
+            .map(|index| Island {
+                index,
+                migration,
+                inboxes: Arc::clone(&inboxes),
+            })
+            .collect()
+    }
+
+    /// the islands that the next migration goes to
+    fn destinations(&self, rng: &mut Rando) -> Vec<usize> {
+        let n = self.inboxes.len();
+        if n < 2 {
+            return vec![];
+        }
+        match self.migration.topology {
+            Topology::Ring => vec![(self.index + 1) % n],
+            Topology::FullyConnected => (0..n).filter(|i| *i != self.index).collect(),
+            Topology::Random => vec![(self.index + 1 + rng.gen_range(0..n - 1)) % n],
+        }
+    }
+
+    /// send the `count` candidates with the highest ratings.   If a destination has finished its cycle its inbox is capped rather than growing forever.
+    pub fn emigrate(&self, population: &[Candidate<S>], ratings: &[usize], rng: &mut Rando) {
+        let mut order: Vec<usize> = (0..population.len()).collect();
+        order.sort_by(|a, b| ratings[*b].cmp(&ratings[*a]));
+        let emigrants: Vec<&Candidate<S>> = order
+            .iter()
+            .take(self.migration.count)
+            .map(|i| &population[*i])
+            .collect();
+        let cap = self.migration.count * self.inboxes.len();
+        for destination in self.destinations(rng) {
+            let mut inbox = self.inboxes[destination].lock().unwrap();
+            inbox.extend(emigrants.iter().map(|c| (*c).clone()));
+            if inbox.len() > cap {
+                let excess = inbox.len() - cap;
+                inbox.drain(..excess);
+            }
+        }
+    }
+
+    /// replace candidates from the end of the population with the candidates that have arrived.   `population[0]` is never replaced, and immigrants that are already in the population are dropped.   Returns the indices of the replaced candidates.
+    pub fn immigrate(&self, population: &mut [Candidate<S>]) -> Vec<usize> {
+        let arrivals = std::mem::take(&mut *self.inboxes[self.index].lock().unwrap());
+        let mut slot = population.len();
+        let mut replaced = vec![];
+        for immigrant in arrivals {
+            if slot <= 1 {
+                break;
+            }
+            if !population
+                .iter()
+                .any(|c| c.chromosone == immigrant.chromosone)
+            {
+                slot -= 1;
+                population[slot] = immigrant;
+                replaced.push(slot);
+            }
+        }
+        replaced
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chromosone::Fixed;
+    use crate::gas::Gas;
+    use mockall::predicate;
+
+    #[test]
+    fn test_ring() {
+        let gas = Gas::dut();
+        let mut rng = Rando::default();
+        let islands = Island::<Fixed<5, 3>>::archipelago(3, Migration::new(Topology::Ring, 10, 2));
+        let population = vec![
+            Candidate::from_chromosone(&gas, [0, 0, 0, 0, 0]),
+            Candidate::from_chromosone(&gas, [1, 1, 1, 1, 1]),
+            Candidate::from_chromosone(&gas, [2, 2, 2, 2, 2]),
+        ];
+        islands[0].emigrate(&population, &[1000, 900, 1100], &mut rng);
+
+        let mut other = vec![
+            Candidate::from_chromosone(&gas, [0, 1, 2, 0, 1]),
+            Candidate::from_chromosone(&gas, [1, 2, 0, 1, 2]),
+            Candidate::from_chromosone(&gas, [2, 2, 2, 2, 2]),
+        ];
+        islands[2].immigrate(&mut other);
+        assert_eq!(other[2].chromosone, [2, 2, 2, 2, 2]);
+
+        assert_eq!(islands[1].immigrate(&mut other), vec![2]);
+        assert_eq!(other[0].chromosone, [0, 1, 2, 0, 1]);
+        assert_eq!(other[1].chromosone, [1, 2, 0, 1, 2]);
+        assert_eq!(other[2].chromosone, [0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_fully_connected() {
+        let islands =
+            Island::<Fixed<5, 3>>::archipelago(4, Migration::new(Topology::FullyConnected, 10, 2));
+        let mut rng = Rando::default();
+        assert_eq!(islands[2].destinations(&mut rng), vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn test_random() {
+        let islands =
+            Island::<Fixed<5, 3>>::archipelago(4, Migration::new(Topology::Random, 10, 2));
+        let mut rng = Rando::default();
+        for draw in 0..3usize {
+            rng.expect_gen_range()
+                .with(predicate::eq(0..3))
+                .times(1)
+                .return_const(draw);
+        }
+        // every draw is one of the other islands, never island 1 itself
+        let destinations: Vec<usize> = (0..3)
+            .flat_map(|_| islands[1].destinations(&mut rng))
+            .collect();
+        assert_eq!(destinations, vec![2, 3, 0]);
+    }
+}
@@ -2,24 +2,55 @@
 
 This module wraps some of the standard rand::* functions in a trait so that they can be mocked for testing.
 
+The generator is a [ChaCha8Rng] rather than the [ThreadRng] so that it can be seeded.  Two [Rando]'s created with the same [Rando::seed_from_u64] produce identical streams, so a run of [Gas::cycle] or a [Pool] can be reproduced exactly.  ChaCha8 is used rather than [StdRng] because the algorithm behind [StdRng] may change between releases of rand.
+
  */
 
 #[cfg(test)]
 use mockall::*;
 
 use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+#[cfg(doc)]
+use crate::gas::{checkpoint::Checkpoint, Gas};
+#[cfg(doc)]
+use crate::pool::Pool;
+#[cfg(doc)]
+use rand::rngs::{StdRng, ThreadRng};
 
+/// Cloning or serializing a [Rando] captures the state of its stream, which is how a [Checkpoint] resumes exactly where it left off.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rando {
-    pub rng: ThreadRng,
+    pub rng: ChaCha8Rng,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub weighted_distribution: Option<Box<rand::distributions::WeightedIndex<usize>>>,
 }
 
 #[cfg_attr(test, automock)]
 #[cfg_attr(test, allow(dead_code))]
 impl Rando {
+    /// seeded from the operating system, so every run is different.
     pub fn new() -> Self {
         Self {
-            rng: ThreadRng::default(),
+            rng: ChaCha8Rng::from_entropy(),
+            weighted_distribution: None,
+        }
+    }
+
+    /// a reproducible generator: the same seed always produces the same stream.
+    pub fn seed_from_u64(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            weighted_distribution: None,
+        }
+    }
+
+    /// derive a new generator from this one.   The new generator's stream is independent of this one's, and this generator is advanced so that successive forks are independent of each other.   Used to hand each thread of a [Pool] its own generator.
+    pub fn fork(&mut self) -> Self {
+        Self {
+            rng: ChaCha8Rng::from_rng(&mut self.rng).unwrap(),
             weighted_distribution: None,
         }
     }
@@ -32,38 +63,80 @@ impl Rando {
         self.rng.gen_range(range)
     }
 
+    pub fn gen_range_f64(&mut self, range: std::ops::Range<f64>) -> f64 {
+        self.rng.gen_range(range)
+    }
+
+    /// the iterator runs on a [Rando::fork] of this generator, so each call returns a different stream.
     #[cfg(not(test))]
     pub fn uniform_iter(
-        &self,
+        &mut self,
         range: std::ops::Range<usize>,
-    ) -> rand::distributions::DistIter<rand::distributions::Uniform<usize>, ThreadRng, usize> {
-        rand::distributions::Uniform::from(range).sample_iter(self.rng.clone())
+    ) -> rand::distributions::DistIter<rand::distributions::Uniform<usize>, ChaCha8Rng, usize> {
+        rand::distributions::Uniform::from(range).sample_iter(self.fork().rng)
     }
 
     #[cfg(test)]
     pub fn uniform_iter(
-        &self,
+        &mut self,
         _range: std::ops::Range<usize>,
     ) -> std::iter::Cloned<std::slice::Iter<'static, usize>> {
         [0usize].iter().cloned()
     }
 
+    /// the iterator runs on a [Rando::fork] of this generator, so each call returns a different stream.
     #[cfg(not(test))]
     pub fn weighted_iter(
-        &self,
+        &mut self,
         weights: &[usize],
-    ) -> rand::distributions::DistIter<rand::distributions::WeightedIndex<usize>, ThreadRng, usize>
+    ) -> rand::distributions::DistIter<rand::distributions::WeightedIndex<usize>, ChaCha8Rng, usize>
     {
         rand::distributions::WeightedIndex::new(weights)
             .unwrap()
-            .sample_iter(self.rng.clone())
+            .sample_iter(self.fork().rng)
     }
 
     #[cfg(test)]
     pub fn weighted_iter(
-        &self,
+        &mut self,
         _weights: &[usize],
     ) -> std::iter::Cloned<std::slice::Iter<'static, usize>> {
         [0usize].iter().cloned()
     }
 }
+
+/// a mock has no stream to capture, so a clone is a fresh mock with no expectations.
+#[cfg(test)]
+impl Clone for MockRando {
+    fn clone(&self) -> Self {
+        MockRando::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed() {
+        let mut a = Rando::seed_from_u64(42);
+        let mut b = Rando::seed_from_u64(42);
+        let sa: Vec<usize> = (0..10).map(|_| a.gen_range(0..1000)).collect();
+        let sb: Vec<usize> = (0..10).map(|_| b.gen_range(0..1000)).collect();
+        assert_eq!(sa, sb);
+    }
+
+    #[test]
+    fn test_fork() {
+        let mut a = Rando::seed_from_u64(42);
+        let mut b = Rando::seed_from_u64(42);
+        let mut a1 = a.fork();
+        let mut a2 = a.fork();
+        let mut b1 = b.fork();
+        let s1: Vec<usize> = (0..10).map(|_| a1.gen_range(0..1000)).collect();
+        let s2: Vec<usize> = (0..10).map(|_| a2.gen_range(0..1000)).collect();
+        let sb: Vec<usize> = (0..10).map(|_| b1.gen_range(0..1000)).collect();
+        assert_ne!(s1, s2);
+        assert_eq!(s1, sb);
+    }
+}
@@ -0,0 +1,154 @@
+/*!
+
+Stopping criteria decide when the phases of a [Gas::cycle] end.   They are configured in [Gas::stopping] via a [StoppingConfig].
+
+Each [StoppingCriterion] looks at the [CycleStats] of the running cycle after every generation and decides whether to fire.  The criteria may be combined with [Any] and [All]:
+
+```
+# use gas::stopping::*;
+# use std::time::Duration;
+// stop after 10 seconds, or after sampling winners for as long as it took to stagnate
+let stop = Any::new(vec![
+    Box::new(Deadline::new(Duration::from_secs(10))),
+    Box::new(SamplingLength::new(1)),
+]);
+```
+
+A cycle with a fixed budget and a seed always produces the same result:
+
+```
+# use gas::Gas;
+# use gas::chromosone::Fixed;
+# use gas::fitness::{self, FitnessConfig};
+# use gas::mutation::{self, MutationConfig};
+# use gas::gas::cycle::CycleProgress;
+# use gas::rando::Rando;
+# use gas::stopping::*;
+# use std::sync::atomic::AtomicBool;
+# use std::sync::Arc;
+let gas = Gas {
+  mutations: MutationConfig::new(vec![
+    (1, Box::new(mutation::Null::new())),
+    (1, Box::new(mutation::Mutate::new(1, 6))),
+  ]),
+  stopping: StoppingConfig {
+    stop: Box::new(MaxGenerations::new(50)),
+    ..StoppingConfig::default()
+  },
+  ..Gas::new(
+    Fixed::<4, 6>::new(),
+    FitnessConfig::new(vec![
+      Box::new(fitness::LocusDesirability::new(vec![vec![1.0, 0.0, 0.0, 2.0]; 6], 1.0)),
+    ]),
+  )
+};
+let sigint = Arc::new(AtomicBool::new(false));
+let first = gas.cycle(&mut CycleProgress::new(&gas, &sigint), Rando::seed_from_u64(7));
+let second = gas.cycle(&mut CycleProgress::new(&gas, &sigint), Rando::seed_from_u64(7));
+assert_eq!(first.chromosone, second.chromosone);
+```
+
+ */
+
+pub mod all;
+pub mod any;
+pub mod deadline;
+pub mod ema_crossover;
+pub mod max_evaluations;
+pub mod max_generations;
+pub mod no_improvement;
+pub mod sampling_length;
+pub mod target_score;
+pub mod target_violations;
+pub mod violations_stable;
+
+pub use all::All;
+pub use any::Any;
+pub use deadline::Deadline;
+pub use ema_crossover::EmaCrossover;
+pub use max_evaluations::MaxEvaluations;
+pub use max_generations::MaxGenerations;
+pub use no_improvement::NoImprovement;
+pub use sampling_length::SamplingLength;
+pub use target_score::TargetScore;
+pub use target_violations::TargetViolations;
+pub use violations_stable::ViolationsStable;
+
+use crate::gas::cycle::CycleStats;
+
+#[cfg(doc)]
+use crate::gas::{cycle::CycleProgress, cycle::State, Gas};
+
+pub trait StoppingCriterion {
+    /// returns true when the phase of the cycle should end.
+    fn stop(&self, stats: &CycleStats) -> bool;
+    /// how close this criterion is to firing, from 0.0 to 1.0.   Used to calculate [CycleProgress::progress].   Criteria that cannot estimate this return 0.0.
+    fn progress(&self, _stats: &CycleStats) -> f64 {
+        0.0
+    }
+    /// the smoothing constants of the exponential moving averages this criterion reads with [CycleStats::ema].   The cycle only tracks the averages that are requested here.
+    fn emas(&self) -> Vec<f64> {
+        vec![]
+    }
+}
+
+pub struct StoppingConfig {
+    /// while [State::Seeding], the top candidate is added to the seed pool when it has no violations or when this fires.
+    pub seeded: Box<dyn StoppingCriterion + Send + Sync>,
+    /// while [State::Running], the cycle moves on to [State::Stagnated] when this fires.
+    pub stagnated: Box<dyn StoppingCriterion + Send + Sync>,
+    /// checked in every phase.  When it fires the cycle runs the final tournament and returns.
+    pub stop: Box<dyn StoppingCriterion + Send + Sync>,
+}
+
+impl StoppingConfig {
+    pub fn new(
+        seeded: Box<dyn StoppingCriterion + Send + Sync>,
+        stagnated: Box<dyn StoppingCriterion + Send + Sync>,
+        stop: Box<dyn StoppingCriterion + Send + Sync>,
+    ) -> StoppingConfig {
+        StoppingConfig {
+            seeded,
+            stagnated,
+            stop,
+        }
+    }
+
+    /// the smoothing constants requested by all three criteria.
+    pub fn emas(&self) -> Vec<f64> {
+        let mut emas = self.seeded.emas();
+        for c in self.stagnated.emas().into_iter().chain(self.stop.emas()) {
+            if !emas.contains(&c) {
+                emas.push(c);
+            }
+        }
+        emas
+    }
+}
+
+impl Default for StoppingConfig {
+    /// Seeding ends when the violations have been stable for 100 generations.   Running ends when the fast moving average of the score (smoothing 0.995) drops below the slow moving average (0.9995) and the violations have been stable for 100 generations.   The cycle stops when it has sampled for 3 times as long as it took to stagnate, or after 2 << 20 generations.
+    fn default() -> Self {
+        StoppingConfig::new(
+            Box::new(ViolationsStable::new(100)),
+            Box::new(All::new(vec![
+                Box::new(EmaCrossover::new(0.995, 0.9995)),
+                Box::new(ViolationsStable::new(100)),
+            ])),
+            Box::new(Any::new(vec![
+                Box::new(MaxGenerations::new(2 << 20)),
+                Box::new(SamplingLength::new(3)),
+            ])),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_emas() {
+        assert_eq!(StoppingConfig::default().emas(), vec![0.995, 0.9995]);
+    }
+}
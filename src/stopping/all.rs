@@ -0,0 +1,55 @@
+use super::StoppingCriterion;
+use crate::gas::cycle::CycleStats;
+
+/// Fires when every one of its criteria fires.
+pub struct All {
+    pub criteria: Vec<Box<dyn StoppingCriterion + Send + Sync>>,
+}
+
+impl All {
+    pub fn new(criteria: Vec<Box<dyn StoppingCriterion + Send + Sync>>) -> All {
+        All { criteria }
+    }
+}
+
+impl StoppingCriterion for All {
+    fn stop(&self, stats: &CycleStats) -> bool {
+        self.criteria.iter().all(|c| c.stop(stats))
+    }
+
+    /// the progress of the criterion furthest from firing
+    fn progress(&self, stats: &CycleStats) -> f64 {
+        self.criteria
+            .iter()
+            .map(|c| c.progress(stats))
+            .fold(1.0, f64::min)
+    }
+
+    fn emas(&self) -> Vec<f64> {
+        self.criteria.iter().flat_map(|c| c.emas()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stopping::{MaxGenerations, ViolationsStable};
+
+    #[test]
+    fn test_all() {
+        let c = All::new(vec![
+            Box::new(MaxGenerations::new(10)),
+            Box::new(ViolationsStable::new(5)),
+        ]);
+        let mut stats = CycleStats {
+            iteration: 10,
+            n_cur_violations: 3,
+            ..Default::default()
+        };
+        assert!(!c.stop(&stats));
+        assert_eq!(c.progress(&stats), 0.6);
+        stats.n_cur_violations = 6;
+        assert!(c.stop(&stats));
+        assert_eq!(c.progress(&stats), 1.0);
+    }
+}
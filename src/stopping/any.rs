@@ -0,0 +1,58 @@
+use super::StoppingCriterion;
+use crate::gas::cycle::CycleStats;
+
+/// Fires when any one of its criteria fires.
+pub struct Any {
+    pub criteria: Vec<Box<dyn StoppingCriterion + Send + Sync>>,
+}
+
+impl Any {
+    pub fn new(criteria: Vec<Box<dyn StoppingCriterion + Send + Sync>>) -> Any {
+        Any { criteria }
+    }
+}
+
+impl StoppingCriterion for Any {
+    fn stop(&self, stats: &CycleStats) -> bool {
+        self.criteria.iter().any(|c| c.stop(stats))
+    }
+
+    /// the progress of the criterion closest to firing
+    fn progress(&self, stats: &CycleStats) -> f64 {
+        self.criteria
+            .iter()
+            .map(|c| c.progress(stats))
+            .fold(0.0, f64::max)
+    }
+
+    fn emas(&self) -> Vec<f64> {
+        self.criteria.iter().flat_map(|c| c.emas()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stopping::{EmaCrossover, MaxGenerations};
+
+    #[test]
+    fn test_any() {
+        let c = Any::new(vec![
+            Box::new(MaxGenerations::new(10)),
+            Box::new(EmaCrossover::new(0.9, 0.99)),
+        ]);
+        assert_eq!(c.emas(), vec![0.9, 0.99]);
+        let mut stats = CycleStats {
+            iteration: 5,
+            emas: vec![(0.9, 2.0), (0.99, 1.0)],
+            ..Default::default()
+        };
+        assert!(!c.stop(&stats));
+        assert_eq!(c.progress(&stats), 0.5);
+        stats.iteration = 10;
+        assert!(c.stop(&stats));
+        stats.iteration = 5;
+        stats.emas[0].1 = 0.5;
+        assert!(c.stop(&stats));
+    }
+}
@@ -0,0 +1,43 @@
+use super::StoppingCriterion;
+use crate::gas::cycle::CycleStats;
+
+use std::time::Duration;
+
+/// Fires once the cycle has been running for `duration` of wall clock time.
+pub struct Deadline {
+    pub duration: Duration,
+}
+
+impl Deadline {
+    pub const fn new(duration: Duration) -> Deadline {
+        Deadline { duration }
+    }
+}
+
+impl StoppingCriterion for Deadline {
+    fn stop(&self, stats: &CycleStats) -> bool {
+        stats.elapsed >= self.duration
+    }
+
+    fn progress(&self, stats: &CycleStats) -> f64 {
+        stats.elapsed.as_secs_f64() / self.duration.as_secs_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline() {
+        let c = Deadline::new(Duration::from_secs(10));
+        let mut stats = CycleStats {
+            elapsed: Duration::from_secs(5),
+            ..Default::default()
+        };
+        assert!(!c.stop(&stats));
+        assert_eq!(c.progress(&stats), 0.5);
+        stats.elapsed = Duration::from_secs(10);
+        assert!(c.stop(&stats));
+    }
+}
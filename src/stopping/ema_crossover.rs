@@ -0,0 +1,64 @@
+use super::StoppingCriterion;
+use crate::gas::cycle::CycleStats;
+
+#[cfg(doc)]
+use crate::gas::cycle::State;
+
+/// Fires when the fast exponential moving average of the top score drops below the slow moving average, which indicates that the score has stopped improving.
+///
+/// Each generation `average = average * constant + score * (1 - constant)`, so `fast` should be smaller than `slow`.   The averages are only updated while [State::Running].   Never fires if either average is missing from the [CycleStats].
+pub struct EmaCrossover {
+    pub fast: f64,
+    pub slow: f64,
+}
+
+impl EmaCrossover {
+    pub const fn new(fast: f64, slow: f64) -> EmaCrossover {
+        EmaCrossover { fast, slow }
+    }
+}
+
+impl StoppingCriterion for EmaCrossover {
+    fn stop(&self, stats: &CycleStats) -> bool {
+        match (stats.ema(self.fast), stats.ema(self.slow)) {
+            (Some(fast), Some(slow)) => fast < slow,
+            _ => false,
+        }
+    }
+
+    fn emas(&self) -> Vec<f64> {
+        vec![self.fast, self.slow]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ema_crossover() {
+        let c = EmaCrossover::new(0.9, 0.99);
+        let mut stats = CycleStats {
+            emas: vec![(0.9, 2.0), (0.99, 1.0)],
+            ..Default::default()
+        };
+        assert!(!c.stop(&stats));
+        stats.emas[0].1 = 0.5;
+        assert!(c.stop(&stats));
+    }
+
+    #[test]
+    fn test_missing_ema() {
+        let c = EmaCrossover::new(0.9, 0.99);
+        let mut stats = CycleStats {
+            score: 3.0,
+            emas: vec![(0.9, 2.0)],
+            ..Default::default()
+        };
+        assert!(!c.stop(&stats));
+        // resumed with a config that wants 0.99 too
+        stats.track_emas(&c.emas());
+        assert_eq!(stats.ema(0.99), Some(3.0));
+        assert!(c.stop(&stats));
+    }
+}
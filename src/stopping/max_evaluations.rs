@@ -0,0 +1,41 @@
+use super::StoppingCriterion;
+use crate::gas::cycle::CycleStats;
+
+/// Fires once `evaluations` candidates have been created.   Each creation runs every fitness function and constraint, so this is a budget for problems with expensive fitness functions.
+pub struct MaxEvaluations {
+    pub evaluations: usize,
+}
+
+impl MaxEvaluations {
+    pub const fn new(evaluations: usize) -> MaxEvaluations {
+        MaxEvaluations { evaluations }
+    }
+}
+
+impl StoppingCriterion for MaxEvaluations {
+    fn stop(&self, stats: &CycleStats) -> bool {
+        stats.evaluations >= self.evaluations
+    }
+
+    fn progress(&self, stats: &CycleStats) -> f64 {
+        stats.evaluations as f64 / self.evaluations as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_evaluations() {
+        let c = MaxEvaluations::new(1000);
+        let mut stats = CycleStats {
+            evaluations: 250,
+            ..Default::default()
+        };
+        assert!(!c.stop(&stats));
+        assert_eq!(c.progress(&stats), 0.25);
+        stats.evaluations = 1000;
+        assert!(c.stop(&stats));
+    }
+}
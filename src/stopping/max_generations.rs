@@ -0,0 +1,41 @@
+use super::StoppingCriterion;
+use crate::gas::cycle::CycleStats;
+
+/// Fires once `generations` generations have been run.
+pub struct MaxGenerations {
+    pub generations: usize,
+}
+
+impl MaxGenerations {
+    pub const fn new(generations: usize) -> MaxGenerations {
+        MaxGenerations { generations }
+    }
+}
+
+impl StoppingCriterion for MaxGenerations {
+    fn stop(&self, stats: &CycleStats) -> bool {
+        stats.iteration >= self.generations
+    }
+
+    fn progress(&self, stats: &CycleStats) -> f64 {
+        stats.iteration as f64 / self.generations as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_generations() {
+        let c = MaxGenerations::new(200);
+        let mut stats = CycleStats {
+            iteration: 50,
+            ..Default::default()
+        };
+        assert!(!c.stop(&stats));
+        assert_eq!(c.progress(&stats), 0.25);
+        stats.iteration = 200;
+        assert!(c.stop(&stats));
+    }
+}
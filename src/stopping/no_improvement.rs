@@ -0,0 +1,41 @@
+use super::StoppingCriterion;
+use crate::gas::cycle::CycleStats;
+
+/// Fires when the top candidate has not beaten the record for `generations` generations.   A new record is fewer violations, or the same violations and a higher score.
+pub struct NoImprovement {
+    pub generations: usize,
+}
+
+impl NoImprovement {
+    pub const fn new(generations: usize) -> NoImprovement {
+        NoImprovement { generations }
+    }
+}
+
+impl StoppingCriterion for NoImprovement {
+    fn stop(&self, stats: &CycleStats) -> bool {
+        stats.iteration - stats.last_improvement >= self.generations
+    }
+
+    fn progress(&self, stats: &CycleStats) -> f64 {
+        (stats.iteration - stats.last_improvement) as f64 / self.generations as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_improvement() {
+        let c = NoImprovement::new(10);
+        let mut stats = CycleStats {
+            iteration: 15,
+            last_improvement: 10,
+            ..Default::default()
+        };
+        assert!(!c.stop(&stats));
+        stats.iteration = 20;
+        assert!(c.stop(&stats));
+    }
+}
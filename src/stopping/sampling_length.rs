@@ -0,0 +1,50 @@
+use super::StoppingCriterion;
+use crate::gas::cycle::{CycleStats, State};
+
+/// Once the cycle has stagnated, winners are sampled until there are enough of them.   This fires if the sampling takes too long: `multiple` times as many generations as it took to stagnate.
+pub struct SamplingLength {
+    pub multiple: usize,
+}
+
+impl SamplingLength {
+    pub const fn new(multiple: usize) -> SamplingLength {
+        SamplingLength { multiple }
+    }
+}
+
+impl StoppingCriterion for SamplingLength {
+    fn stop(&self, stats: &CycleStats) -> bool {
+        stats.state == State::Stagnated
+            && stats.iteration > (self.multiple + 1) * stats.stagnation_iteration
+    }
+
+    fn progress(&self, stats: &CycleStats) -> f64 {
+        if stats.state == State::Stagnated {
+            (stats.iteration - stats.stagnation_iteration) as f64
+                / (self.multiple * stats.stagnation_iteration) as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampling_length() {
+        let c = SamplingLength::new(3);
+        let mut stats = CycleStats {
+            iteration: 500,
+            stagnation_iteration: 100,
+            ..Default::default()
+        };
+        assert!(!c.stop(&stats));
+        stats.state = State::Stagnated;
+        assert!(c.stop(&stats));
+        stats.iteration = 250;
+        assert!(!c.stop(&stats));
+        assert_eq!(c.progress(&stats), 0.5);
+    }
+}
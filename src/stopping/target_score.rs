@@ -0,0 +1,39 @@
+use super::StoppingCriterion;
+use crate::gas::cycle::CycleStats;
+
+#[cfg(doc)]
+use crate::candidate::Candidate;
+
+/// Fires when the [Candidate::total_score] of the top candidate reaches `score`.   Combine with [TargetViolations](super::TargetViolations) in an [All](super::All) to also require a valid candidate.
+pub struct TargetScore {
+    pub score: f64,
+}
+
+impl TargetScore {
+    pub const fn new(score: f64) -> TargetScore {
+        TargetScore { score }
+    }
+}
+
+impl StoppingCriterion for TargetScore {
+    fn stop(&self, stats: &CycleStats) -> bool {
+        stats.score >= self.score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_score() {
+        let c = TargetScore::new(10.0);
+        let mut stats = CycleStats {
+            score: 9.5,
+            ..Default::default()
+        };
+        assert!(!c.stop(&stats));
+        stats.score = 10.0;
+        assert!(c.stop(&stats));
+    }
+}
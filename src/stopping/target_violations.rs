@@ -0,0 +1,38 @@
+use super::StoppingCriterion;
+use crate::gas::cycle::CycleStats;
+
+/// Fires when the top candidate has `violations` or fewer constraint violations.
+pub struct TargetViolations {
+    pub violations: usize,
+}
+
+impl TargetViolations {
+    pub const fn new(violations: usize) -> TargetViolations {
+        TargetViolations { violations }
+    }
+}
+
+impl StoppingCriterion for TargetViolations {
+    fn stop(&self, stats: &CycleStats) -> bool {
+        stats.violations <= self.violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_violations() {
+        let c = TargetViolations::new(1);
+        let mut stats = CycleStats {
+            violations: 2,
+            ..Default::default()
+        };
+        assert!(!c.stop(&stats));
+        stats.violations = 1;
+        assert!(c.stop(&stats));
+        stats.violations = 0;
+        assert!(c.stop(&stats));
+    }
+}
@@ -0,0 +1,23 @@
+use super::StoppingCriterion;
+use crate::gas::cycle::CycleStats;
+
+/// Fires when the number of violations of the top candidate has stayed the same for more than `generations` generations.   Hopefully the number at that point is 0!
+pub struct ViolationsStable {
+    pub generations: usize,
+}
+
+impl ViolationsStable {
+    pub const fn new(generations: usize) -> ViolationsStable {
+        ViolationsStable { generations }
+    }
+}
+
+impl StoppingCriterion for ViolationsStable {
+    fn stop(&self, stats: &CycleStats) -> bool {
+        stats.n_cur_violations > self.generations
+    }
+
+    fn progress(&self, stats: &CycleStats) -> f64 {
+        f64::min(1.0, stats.n_cur_violations as f64 / self.generations as f64)
+    }
+}
@@ -1,24 +1,44 @@
 pub mod double_elimination;
 pub mod elo;
 pub mod full_season;
+pub mod non_dominated;
+pub mod ranking;
+pub mod sampled_season;
 pub mod single_elimination;
+pub mod swiss;
 
 pub use double_elimination::DoubleElimination;
 pub use full_season::FullSeason;
+pub use non_dominated::NonDominated;
+pub use ranking::Ranking;
+pub use sampled_season::SampledSeason;
 pub use single_elimination::SingleElimination;
+pub use swiss::Swiss;
 
 use crate::candidate::Candidate;
+use crate::chromosone::Shape;
 
 #[mockall_double::double]
 use crate::rando::Rando;
 
 /// A tournament ranks candidates. It returns a winner plus an elo ranking with
-/// average 1000 for each candidate in the population.
-pub trait Tournament<const N: usize, const NSYMS: usize> {
+/// average 1000 for each candidate in the population.   [Tournament::rank] turns those into a full [Ranking].
+pub trait Tournament<S: Shape> {
     fn run(
         &self,
-        population: &Vec<Candidate<N, NSYMS>>,
+        population: &Vec<Candidate<S>>,
         rng: &mut Rando,
         score_weights: &Vec<f64>,
-    ) -> (Candidate<N, NSYMS>, Vec<usize>);
+    ) -> (Candidate<S>, Vec<usize>);
+
+    /// the whole population in order, with ties marked.  See [ranking].
+    fn rank(
+        &self,
+        population: &Vec<Candidate<S>>,
+        rng: &mut Rando,
+        score_weights: &Vec<f64>,
+    ) -> Ranking<S> {
+        let (winner, ratings) = self.run(population, rng, score_weights);
+        Ranking::new(population, &winner, &ratings)
+    }
 }
@@ -1,5 +1,7 @@
+use crate::chromosone::Shape;
 use std::collections::VecDeque;
 
+use super::elo::round_ratings;
 use super::single_elimination::SingleElimination;
 use super::Tournament;
 use crate::candidate::Candidate;
@@ -8,27 +10,25 @@ use crate::game::Game;
 #[mockall_double::double]
 use crate::rando::Rando;
 
-pub struct DoubleElimination<G: Game<N, NSYMS> + Clone, const N: usize, const NSYMS: usize> {
+pub struct DoubleElimination<G> {
     pub game: G,
 }
 
-impl<G: Game<N, NSYMS> + Clone, const N: usize, const NSYMS: usize> DoubleElimination<G, N, NSYMS> {
-    pub const fn new(game: G) -> DoubleElimination<G, N, NSYMS> {
+impl<G> DoubleElimination<G> {
+    pub const fn new(game: G) -> DoubleElimination<G> {
         DoubleElimination { game }
     }
 }
 
-impl<G: Game<N, NSYMS> + Clone, const N: usize, const NSYMS: usize> Tournament<N, NSYMS>
-    for DoubleElimination<G, N, NSYMS>
-{
+impl<S: Shape, G: Game<S> + Clone> Tournament<S> for DoubleElimination<G> {
     fn run(
         &self,
-        population: &Vec<Candidate<N, NSYMS>>,
+        population: &Vec<Candidate<S>>,
         rng: &mut Rando,
         score_weights: &Vec<f64>,
-    ) -> (Candidate<N, NSYMS>, Vec<usize>) {
+    ) -> (Candidate<S>, Vec<usize>) {
         let mut remaining: VecDeque<usize> = VecDeque::with_capacity(population.len());
-        let mut rating = vec![1000usize; population.len()];
+        let mut rating = vec![1000.0; population.len()];
 
         for i in 0..population.len() {
             remaining.push_back(i);
@@ -59,7 +59,7 @@ impl<G: Game<N, NSYMS> + Clone, const N: usize, const NSYMS: usize> Tournament<N
             score_weights,
         );
 
-        (population[winner].clone(), rating)
+        (population[winner].clone(), round_ratings(&rating))
     }
     /*
         let mut roundA: VecDeque<usize> = VecDeque::with_capacity(population.len());
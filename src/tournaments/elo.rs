@@ -1,8 +1,102 @@
-pub fn elo(k: f64, rwinner: usize, rloser: usize) -> (usize, usize) {
-    let qw = f64::powf(10.0, rwinner as f64 / 400.0);
-    let ql = f64::powf(10.0, rloser as f64 / 400.0);
+/// the ELO ratings of the winner and the loser of a game after it is played, with K factor `k`.   Kept as `f64`s so that small updates aren't lost to truncation; a rating never goes below 0.
+pub fn elo(k: f64, rwinner: f64, rloser: f64) -> (f64, f64) {
+    let qw = f64::powf(10.0, rwinner / 400.0);
+    let ql = f64::powf(10.0, rloser / 400.0);
     let el = ql / (qw + ql);
-    (rwinner + (k * el) as usize, rloser - (k * el) as usize)
+    (rwinner + k * el, f64::max(rloser - k * el, 0.0))
+}
+
+/// `ratings` rounded to the nearest whole number, as a [Tournament](super::Tournament) returns them
+pub fn round_ratings(ratings: &[f64]) -> Vec<usize> {
+    ratings.iter().map(|r| r.round() as usize).collect()
+}
+
+/// ELO ratings averaging about 1000 for a population in which candidate `i` beat `wins[i]` of the others, as if in a [FullSeason](super::FullSeason).
+pub fn season_ratings(wins: &[usize]) -> Vec<usize> {
+    // each player has win probability of wins/popsize.   That means ELO delta between 1/popsize and (popsize-1)/popsize probabilities is log10(popsize)*400.  Center on 1000 to make that average ELO.  Clamp popsize to 10000 so that ELO can't go below 0.
+    let elo_spread = 400.0 * f64::log10(wins.len() as f64);
+    let elo_delta = elo_spread / wins.len() as f64;
+    let elo_base = 1000 - (elo_spread / 2.0) as usize;
+    wins.iter()
+        .map(|w| elo_base + (elo_delta * (*w as f64 + 0.5)) as usize)
+        .collect()
+}
+
+/// an ELO rating accumulated over the tournaments a candidate has been rated in, so that a candidate that survives from one generation to the next keeps its record.   A single tournament is noisy, and a [SingleElimination](super::SingleElimination) gives most of the losers much the same rating, while the average over several tournaments is a much better guide to which candidates should be parents.   See [Gas::rating_memory](crate::gas::Gas::rating_memory).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rating {
+    /// kept as an `f64` so that small updates aren't lost to truncation
+    pub rating: f64,
+    /// the number of tournaments it has been rated in
+    pub tournaments: usize,
+    /// the uncertainty in `rating`, if it is kept by a [Glicko]
+    pub deviation: f64,
+}
+
+impl Rating {
+    /// fold in the rating from one more tournament, averaging over the last `memory` or so tournaments.   The first tournament, or any tournament when `memory` is 1, replaces the rating.   Returns the new rating.
+    pub fn update(&mut self, rating: usize, memory: usize) -> f64 {
+        self.tournaments += 1;
+        let n = usize::min(self.tournaments, memory.max(1));
+        self.rating += (rating as f64 - self.rating) / n as f64;
+        self.rating
+    }
+}
+
+/**
+A Glicko-style rating system, which tracks how certain each [Rating] is as well as the rating itself.
+
+The rating a single tournament gives a candidate is treated as a noisy measurement of its true rating, with a deviation of `tournament_deviation`.   A child's first tournament rating is taken as is, so it starts out with that much uncertainty.   Each later tournament is combined with what is already known, weighting each by how certain it is, so the deviation of a candidate that survives generation after generation shrinks and its rating settles down.   Before each tournament the deviation grows by `drift`, as in Glicko, so that old evidence slowly counts for less as the population around the candidate improves.
+
+Parents are chosen by `rating - confidence * deviation`.   A positive `confidence` prefers well established candidates over lucky newcomers; a negative one gives uncertain candidates the benefit of the doubt.   See [Gas::glicko](crate::gas::Gas::glicko).
+ */
+#[derive(Clone, Debug)]
+pub struct Glicko {
+    pub tournament_deviation: f64,
+    pub drift: f64,
+    pub confidence: f64,
+}
+
+impl Glicko {
+    /// panics unless `tournament_deviation` is greater than 0: with no uncertainty in a tournament there is nothing to weight the ratings by, and they come out NaN
+    pub const fn new(tournament_deviation: f64, drift: f64, confidence: f64) -> Glicko {
+        let glicko = Glicko {
+            tournament_deviation,
+            drift,
+            confidence,
+        };
+        glicko.check();
+        glicko
+    }
+
+    /// panics unless `tournament_deviation` is greater than 0.   [Glicko::new] checks this, and so does [Gas::validate](crate::gas::Gas::validate) for one built from its fields.
+    pub const fn check(&self) {
+        assert!(
+            self.tournament_deviation > 0.0,
+            "the tournament deviation must be greater than 0"
+        );
+    }
+
+    /// fold in the rating `r` from one more tournament.   Returns the new rating.
+    pub fn update(&self, r: &mut Rating, rating: usize) -> f64 {
+        let measured = self.tournament_deviation.powi(2);
+        if r.tournaments == 0 {
+            r.rating = rating as f64;
+            r.deviation = self.tournament_deviation;
+        } else {
+            let prior = r.deviation.powi(2) + self.drift.powi(2);
+            r.rating = (r.rating * measured + rating as f64 * prior) / (prior + measured);
+            r.deviation = f64::sqrt(prior * measured / (prior + measured));
+        }
+        r.tournaments += 1;
+        r.rating
+    }
+
+    /// the weight of `r` in parent selection
+    pub fn weight(&self, r: &Rating) -> f64 {
+        r.rating - self.confidence * r.deviation
+    }
 }
 
 #[cfg(test)]
@@ -10,8 +104,46 @@ mod tests {
     use super::*;
     #[test]
     fn test_elo() {
-        assert_eq!(elo(200.0, 1000, 1000), (1100, 900));
-        assert_eq!(elo(200.0, 1200, 800), (1218, 782));
-        assert_eq!(elo(200.0, 800, 1200), (981, 1019));
+        assert_eq!(elo(200.0, 1000.0, 1000.0), (1100.0, 900.0));
+        let (w, l) = elo(200.0, 1200.0, 800.0);
+        assert_eq!(round_ratings(&[w, l]), [1218, 782]);
+        let (w, l) = elo(200.0, 800.0, 1200.0);
+        assert_eq!(round_ratings(&[w, l]), [982, 1018]);
+        // a rating never goes negative
+        assert_eq!(elo(200.0, 0.0, 100.0).1, 0.0);
+    }
+
+    #[test]
+    fn test_rating() {
+        let mut r = Rating::default();
+        assert_eq!(r.update(1100, 3), 1100.0);
+        assert_eq!(r.update(1000, 3), 1050.0);
+        assert_eq!(r.update(950, 3), 1016.6666666666666);
+        // only the last 3 or so count
+        assert_eq!(r.update(1100, 3), 1044.4444444444443);
+        assert_eq!(r.tournaments, 4);
+
+        let mut once = Rating::default();
+        once.update(1100, 1);
+        assert_eq!(once.update(900, 1), 900.0);
+    }
+
+    #[test]
+    fn test_glicko() {
+        let glicko = Glicko::new(200.0, 50.0, 1.0);
+        let mut r = Rating::default();
+        assert_eq!(glicko.update(&mut r, 1100), 1100.0);
+        assert_eq!(r.deviation, 200.0);
+        assert_eq!(glicko.weight(&r), 900.0);
+        // the second tournament counts for a little more, as the first has drifted
+        assert_eq!(glicko.update(&mut r, 900), 996.969696969697);
+        assert_eq!(r.deviation, 143.5481125130547);
+        assert_eq!(glicko.weight(&r), 853.4215844566423);
+    }
+
+    #[test]
+    #[should_panic(expected = "the tournament deviation must be greater than 0")]
+    fn test_glicko_no_deviation() {
+        Glicko::new(0.0, 0.0, 1.0);
     }
 }
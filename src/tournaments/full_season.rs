@@ -1,29 +1,28 @@
 use crate::candidate::Candidate;
+use crate::chromosone::Shape;
 use crate::game::{self, Game};
-use crate::tournaments::{single_elimination::SingleElimination, Tournament};
+use crate::tournaments::{elo::season_ratings, single_elimination::SingleElimination, Tournament};
 
 #[mockall_double::double]
 use crate::rando::Rando;
 
-pub struct FullSeason<G: Game<N, NSYMS> + Clone, const N: usize, const NSYMS: usize> {
+pub struct FullSeason<G> {
     pub game: G,
 }
 
-impl<G: Game<N, NSYMS> + Clone, const N: usize, const NSYMS: usize> FullSeason<G, N, NSYMS> {
-    pub const fn new(game: G) -> FullSeason<G, N, NSYMS> {
+impl<G> FullSeason<G> {
+    pub const fn new(game: G) -> FullSeason<G> {
         FullSeason { game }
     }
 }
 
-impl<G: Game<N, NSYMS> + Clone, const N: usize, const NSYMS: usize> Tournament<N, NSYMS>
-    for FullSeason<G, N, NSYMS>
-{
+impl<S: Shape, G: Game<S> + Clone> Tournament<S> for FullSeason<G> {
     fn run(
         &self,
-        population: &Vec<Candidate<N, NSYMS>>,
+        population: &Vec<Candidate<S>>,
         rng: &mut Rando,
         score_weights: &Vec<f64>,
-    ) -> (Candidate<N, NSYMS>, Vec<usize>) {
+    ) -> (Candidate<S>, Vec<usize>) {
         let mut wins = vec![0usize; population.len()];
 
         for left in 0..population.len() - 1 {
@@ -42,21 +41,12 @@ impl<G: Game<N, NSYMS> + Clone, const N: usize, const NSYMS: usize> Tournament<N
             }
         }
 
-        let mut winners = Vec::<Candidate<N, NSYMS>>::new();
         let max = wins.iter().fold(0usize, |max, w| max.max(*w));
-
-        // each player has win probability of wins/popsize.   That means ELO delta between 1/popsize and (popsize-1)/popsize probabilities is log10(popsize)*400.  Center on 1000 to make that average ELO.  Clamp popsize to 10000 so that ELO can't go below 0.
-        let elo_spread = 400.0 * f64::log10(population.len() as f64);
-        let elo_delta = elo_spread / population.len() as f64;
-        let elo_base = 1000 - (elo_spread / 2.0) as usize;
-
-        for i in 0..population.len() {
-            if wins[i] == max {
-                winners.push(population[i].clone());
-            }
-
-            wins[i] = elo_base + (elo_delta * (wins[i] as f64 + 0.5)) as usize;
-        }
+        let winners: Vec<Candidate<S>> = (0..population.len())
+            .filter(|i| wins[*i] == max)
+            .map(|i| population[i].clone())
+            .collect();
+        let wins = season_ratings(&wins);
 
         if winners.len() > 1 {
             (
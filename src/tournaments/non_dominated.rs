@@ -0,0 +1,161 @@
+use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+use crate::tournaments::{elo::season_ratings, Tournament};
+
+#[mockall_double::double]
+use crate::rando::Rando;
+
+/**
+An NSGA-II style tournament that ranks the population by Pareto fronts rather than by playing games.
+
+The first front are the candidates that no other candidate [dominates](Candidate::dominates), the second front those only dominated by the first, and so on.   Like in the games, constraint violations come first: a candidate with fewer violations dominates any candidate with more.   Within a front the candidates in the least crowded parts of the front (see [Candidate::crowding]) rank higher, which keeps the population spread out along the front.
+
+Pairwise games compare two candidates at a time on a sample or a sum of the scores, which gets noisy when there are hundreds of scores.   Dominance uses every score and is deterministic, so this tournament never touches the [Rando].
+
+The winner is the candidate in the first front with the highest [Candidate::total_score], and it is ranked above the rest of its front so that the elites the ratings pick agree with it.   The ratings are the same ELO-like ratings a [FullSeason](super::FullSeason) gives, as if each candidate beat every candidate ranked below it.
+ */
+pub struct NonDominated {}
+
+impl Default for NonDominated {
+    fn default() -> Self {
+        NonDominated::new()
+    }
+}
+
+impl NonDominated {
+    pub const fn new() -> NonDominated {
+        NonDominated {}
+    }
+
+    /// the indices of `population` split into Pareto fronts, best front first
+    pub fn fronts<S: Shape>(population: &[Candidate<S>]) -> Vec<Vec<usize>> {
+        let n = population.len();
+        // the candidates each candidate dominates, and the number of candidates that dominate it
+        let mut dominated = vec![Vec::<usize>::new(); n];
+        let mut dominators = vec![0usize; n];
+        for i in 0..n {
+            for j in i + 1..n {
+                if population[i].dominates(&population[j]) {
+                    dominated[i].push(j);
+                    dominators[j] += 1;
+                } else if population[j].dominates(&population[i]) {
+                    dominated[j].push(i);
+                    dominators[i] += 1;
+                }
+            }
+        }
+
+        let mut fronts = Vec::<Vec<usize>>::new();
+        let mut front: Vec<usize> = (0..n).filter(|i| dominators[*i] == 0).collect();
+        while !front.is_empty() {
+            let mut next = Vec::<usize>::new();
+            for i in front.iter() {
+                for j in dominated[*i].iter() {
+                    dominators[*j] -= 1;
+                    if dominators[*j] == 0 {
+                        next.push(*j);
+                    }
+                }
+            }
+            fronts.push(front);
+            front = next;
+        }
+        fronts
+    }
+}
+
+impl<S: Shape> Tournament<S> for NonDominated {
+    fn run(
+        &self,
+        population: &Vec<Candidate<S>>,
+        _rng: &mut Rando,
+        score_weights: &Vec<f64>,
+    ) -> (Candidate<S>, Vec<usize>) {
+        let fronts = NonDominated::fronts(population);
+        let winner = fronts[0]
+            .iter()
+            .copied()
+            .reduce(|best, i| {
+                if population[i].total_score(score_weights)
+                    > population[best].total_score(score_weights)
+                {
+                    i
+                } else {
+                    best
+                }
+            })
+            .unwrap();
+
+        // rank by front, then the winner, then by crowding within the front
+        let mut ranked = Vec::<(usize, (usize, bool), f64)>::with_capacity(population.len());
+        for (f, front) in fronts.iter().enumerate() {
+            let members: Vec<&Candidate<S>> = front.iter().map(|i| &population[*i]).collect();
+            let crowding = Candidate::crowding(&members);
+            ranked.extend(
+                front
+                    .iter()
+                    .zip(crowding)
+                    .map(|(i, c)| (*i, (f, *i != winner), c)),
+            );
+        }
+        ranked.sort_by(|a, b| a.1.cmp(&b.1).then(b.2.total_cmp(&a.2)));
+
+        // everyone ranked strictly lower counts as a win
+        let mut wins = vec![0usize; population.len()];
+        let mut start = 0;
+        while start < ranked.len() {
+            let (_, front, crowding) = ranked[start];
+            let end = start
+                + ranked[start..]
+                    .iter()
+                    .take_while(|r| r.1 == front && r.2 == crowding)
+                    .count();
+            for r in ranked[start..end].iter() {
+                wins[r.0] = ranked.len() - end;
+            }
+            start = end;
+        }
+
+        (population[winner].clone(), season_ratings(&wins))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chromosone::Fixed;
+
+    fn candidate(gene: u8, scores: Vec<f64>, violations: usize) -> Candidate<Fixed<5, 3>> {
+        Candidate {
+            chromosone: [gene; 5],
+            scores,
+            violations,
+        }
+    }
+
+    #[test]
+    fn test_non_dominated() {
+        let pop = vec![
+            candidate(0, vec![1.0, 1.0], 0),
+            candidate(1, vec![0.0, 4.0], 0),
+            candidate(2, vec![9.0, 9.0], 1),
+            candidate(3, vec![4.0, 0.0], 0),
+            candidate(4, vec![2.0, 2.0], 0),
+            candidate(5, vec![3.0, 3.0], 0),
+        ];
+        assert_eq!(
+            NonDominated::fronts(&pop),
+            vec![vec![1, 3, 5], vec![4], vec![0], vec![2]]
+        );
+
+        let mut r = Rando::default();
+        let (winner, ratings) = NonDominated::new().run(&pop, &mut r, &vec![1.0, 1.0]);
+        Candidate::assert_eq(&winner, &pop[5]);
+        // the winner is rated highest, then 1 and 3 are tied at the ends of the first front
+        assert!(ratings[5] > ratings[1]);
+        assert_eq!(ratings[1], ratings[3]);
+        assert!(ratings[3] > ratings[4]);
+        assert!(ratings[4] > ratings[0]);
+        assert!(ratings[0] > ratings[2]);
+    }
+}
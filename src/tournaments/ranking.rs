@@ -1 +1,143 @@
+/*!
 
+A [Ranking] is the whole population ordered by the ratings a [Tournament] gave it, rather than just the winner.   Every tournament provides one through [Tournament::rank].
+
+The tournament winner always comes first.   The rest follow in order of rating, and candidates with the same rating share a rank and are marked as [Ranked::tied].   In a [FullSeason](super::FullSeason), for instance, the candidates that won the same number of games are tied.
+
+```
+# use gas::candidate::Candidate;
+# use gas::chromosone::Fixed;
+# use gas::game;
+# use gas::rando::Rando;
+# use gas::tournaments::{FullSeason, Tournament};
+let candidate = |gene, scores| Candidate::<Fixed<3, 5>> { chromosone: [gene; 3], scores, violations: 0 };
+let population = vec![
+  candidate(0, vec![0.0, 0.0, 0.0]),
+  // rock, paper and scissors each beat one of the others
+  candidate(1, vec![3.0, 2.0, 1.0]),
+  candidate(2, vec![1.0, 3.0, 2.0]),
+  candidate(3, vec![2.0, 1.0, 3.0]),
+  candidate(4, vec![4.0, 4.0, 4.0]),
+];
+let tournament = FullSeason::new(game::Full::new());
+let ranking = tournament.rank(&population, &mut Rando::seed_from_u64(1), &vec![1.0; 3]);
+let ranks: Vec<(usize, usize, bool)> = ranking.entries.iter().map(|r| (r.index, r.rank, r.tied)).collect();
+assert_eq!(ranks, vec![(4, 0, false), (1, 1, true), (2, 1, true), (3, 1, true), (0, 4, false)]);
+assert_eq!(ranking.top(1)[0].candidate.chromosone, [4, 4, 4]);
+```
+
+ */
+
+#[cfg(doc)]
+use super::Tournament;
+use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+
+/// a candidate's place in a [Ranking]
+#[derive(Clone, Debug)]
+pub struct Ranked<S: Shape> {
+    pub candidate: Candidate<S>,
+    /// the index of `candidate` in the ranked population
+    pub index: usize,
+    /// the rating the [Tournament] gave it
+    pub rating: usize,
+    /// 0 for the winner.   Tied candidates share the rank of the first of them, so ranks may skip: 0, 1, 1, 3.
+    pub rank: usize,
+    /// another candidate has the same rank
+    pub tied: bool,
+}
+
+/// see module documentation
+#[derive(Clone, Debug)]
+pub struct Ranking<S: Shape> {
+    /// best first
+    pub entries: Vec<Ranked<S>>,
+}
+
+impl<S: Shape> Ranking<S> {
+    /// rank `population` from the results of [Tournament::run]
+    pub fn new(
+        population: &[Candidate<S>],
+        winner: &Candidate<S>,
+        ratings: &[usize],
+    ) -> Ranking<S> {
+        assert_eq!(population.len(), ratings.len());
+        let mut order: Vec<usize> = (0..population.len()).collect();
+        order.sort_by(|a, b| ratings[*b].cmp(&ratings[*a]));
+        // the winner may be tied with others, it's ahead of them all
+        if let Some(w) = order
+            .iter()
+            .position(|i| population[*i].chromosone == winner.chromosone)
+        {
+            let w = order.remove(w);
+            order.insert(0, w);
+        }
+
+        let mut entries = Vec::<Ranked<S>>::with_capacity(population.len());
+        for (k, i) in order.into_iter().enumerate() {
+            // nobody is tied with the winner
+            let rank = match entries.last() {
+                Some(prev) if k > 1 && prev.rating == ratings[i] => prev.rank,
+                _ => k,
+            };
+            let tied = k > 0 && entries[k - 1].rank == rank;
+            if tied {
+                entries[k - 1].tied = true;
+            }
+            entries.push(Ranked {
+                candidate: population[i].clone(),
+                index: i,
+                rating: ratings[i],
+                rank,
+                tied,
+            });
+        }
+        Ranking { entries }
+    }
+
+    /// the best `k` entries, or all of them if there are fewer
+    pub fn top(&self, k: usize) -> &[Ranked<S>] {
+        &self.entries[..usize::min(k, self.entries.len())]
+    }
+
+    pub fn winner(&self) -> &Candidate<S> {
+        &self.entries[0].candidate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chromosone::Fixed;
+
+    #[test]
+    fn test_ranking() {
+        let population: Vec<Candidate<Fixed<5, 3>>> = (0..5)
+            .map(|g| Candidate {
+                chromosone: [g; 5],
+                scores: vec![],
+                violations: 0,
+            })
+            .collect();
+        // 1 won a tie break against 3
+        let ranking = Ranking::new(&population, &population[1], &[900, 1100, 1000, 1100, 900]);
+        let order: Vec<(usize, usize, bool)> = ranking
+            .entries
+            .iter()
+            .map(|r| (r.index, r.rank, r.tied))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (1, 0, false),
+                (3, 1, false),
+                (2, 2, false),
+                (0, 3, true),
+                (4, 3, true)
+            ]
+        );
+        assert_eq!(ranking.top(2).len(), 2);
+        assert_eq!(ranking.top(9).len(), 5);
+        assert_eq!(ranking.winner().chromosone, [1; 5]);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+use crate::game::{self, Game};
+use crate::tournaments::{elo::season_ratings, single_elimination::SingleElimination, Tournament};
+
+#[mockall_double::double]
+use crate::rando::Rando;
+
+/**
+A round robin in which each candidate only plays `opponents` randomly drawn opponents rather than the whole population.   The opponents are drawn with replacement, so a candidate may play the same opponent more than once.
+
+A [FullSeason](super::FullSeason) takes `n * (n - 1) / 2` games, while a [SingleElimination] gives most of the losers much the same rating.   Here each candidate plays `opponents` games of its own and also takes part in the games of the candidates that drew it, so it takes `n * opponents` games, and `opponents` trades accuracy against speed.   With `opponents` at `n - 1` it is about as good as a full season.
+
+A candidate's rating comes from its win rate over all the games it took part in, on the same scale as a [FullSeason](super::FullSeason): a candidate that won every game is rated as if it beat all `n - 1` others.   The winner has the best win rate, and ties are broken by a [SingleElimination] between the tied candidates.
+ */
+pub struct SampledSeason<G> {
+    pub game: G,
+    pub opponents: usize,
+}
+
+impl<G> SampledSeason<G> {
+    /// panics if `opponents` is 0
+    pub const fn new(game: G, opponents: usize) -> SampledSeason<G> {
+        assert!(opponents > 0, "a sampled season needs at least 1 opponent");
+        SampledSeason { game, opponents }
+    }
+}
+
+impl<S: Shape, G: Game<S> + Clone> Tournament<S> for SampledSeason<G> {
+    fn run(
+        &self,
+        population: &Vec<Candidate<S>>,
+        rng: &mut Rando,
+        score_weights: &Vec<f64>,
+    ) -> (Candidate<S>, Vec<usize>) {
+        let n = population.len();
+        if n < 2 {
+            return (population[0].clone(), vec![1000; n]);
+        }
+        let mut wins = vec![0usize; n];
+        let mut games = vec![0usize; n];
+
+        for left in 0..n {
+            for _ in 0..self.opponents {
+                // anyone but itself
+                let mut right = rng.gen_range(0..n - 1);
+                if right >= left {
+                    right += 1;
+                }
+                games[left] += 1;
+                games[right] += 1;
+                match self
+                    .game
+                    .run(&population[left], &population[right], rng, score_weights)
+                {
+                    game::LeftRight::Left => {
+                        wins[left] += 1;
+                    }
+                    game::LeftRight::Right => {
+                        wins[right] += 1;
+                    }
+                }
+            }
+        }
+
+        // the wins it would have had in a full season
+        let season_wins: Vec<usize> = (0..n)
+            .map(|i| match games[i] {
+                0 => 0,
+                g => (wins[i] * (n - 1) + g / 2) / g,
+            })
+            .collect();
+        let ratings = season_ratings(&season_wins);
+
+        // compare win rates exactly, rather than the rounded season wins
+        let best = (0..n)
+            .filter(|i| games[*i] > 0)
+            .max_by(|a, b| (wins[*a] * games[*b]).cmp(&(wins[*b] * games[*a])))
+            .unwrap();
+        let winners: Vec<Candidate<S>> = (0..n)
+            .filter(|i| games[*i] > 0 && wins[*i] * games[best] == wins[best] * games[*i])
+            .map(|i| population[i].clone())
+            .collect();
+
+        if winners.len() > 1 {
+            (
+                SingleElimination::new(self.game.clone())
+                    .run(&winners, rng, score_weights)
+                    .0,
+                ratings,
+            )
+        } else {
+            (winners[0].clone(), ratings)
+        }
+    }
+}
+
+#[cfg(test)]
+use mockall::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chromosone::Fixed;
+
+    #[test]
+    fn test_sampled_season() {
+        let pop: Vec<Candidate<Fixed<5, 3>>> = (0..3)
+            .map(|g| Candidate {
+                chromosone: [g; 5],
+                scores: vec![g as f64],
+                violations: 0,
+            })
+            .collect();
+        let mut r = Rando::default();
+        // 0 plays 1, then 1 and 2 both play 0
+        r.expect_gen_range()
+            .with(predicate::eq(0..2))
+            .times(3)
+            .return_const(0usize);
+        // 1 and 2 won all their games so have a play off
+        r.expect_shuffle().times(1).return_const(());
+        let t = SampledSeason::new(game::Full::new(), 1);
+        let (winner, ratings) = t.run(&pop, &mut r, &vec![1.0]);
+        Candidate::assert_eq(&winner, &pop[2]);
+        assert_eq!(ratings, season_ratings(&[0, 2, 2]));
+        assert!(ratings[0] < ratings[1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "a sampled season needs at least 1 opponent")]
+    fn test_no_opponents() {
+        SampledSeason::new(game::Full::new(), 0);
+    }
+}
@@ -1,6 +1,7 @@
+use crate::chromosone::Shape;
 use std::collections::VecDeque;
 
-use super::elo::elo;
+use super::elo::{elo, round_ratings};
 use super::Tournament;
 use crate::candidate::Candidate;
 use crate::game::{self, Game};
@@ -8,12 +9,12 @@ use crate::game::{self, Game};
 #[mockall_double::double]
 use crate::rando::Rando;
 
-pub struct SingleElimination<G: Game<N, NSYMS>, const N: usize, const NSYMS: usize> {
+pub struct SingleElimination<G> {
     pub game: G,
 }
 
-impl<G: Game<N, NSYMS>, const N: usize, const NSYMS: usize> SingleElimination<G, N, NSYMS> {
-    pub const fn new(game: G) -> SingleElimination<G, N, NSYMS> {
+impl<G> SingleElimination<G> {
+    pub const fn new(game: G) -> SingleElimination<G> {
         SingleElimination { game }
     }
 
@@ -22,15 +23,18 @@ impl<G: Game<N, NSYMS>, const N: usize, const NSYMS: usize> SingleElimination<G,
     /// `remaining`: indices into `population` of the candidates that have not yet lost out of the tournament.  By taking 2 candidates from the top and inserting the winner into the bottom, the bye's for tournament sizes not a power of 2 are handled properly.
     /// `rating`: the ELO rating of candidates.   Indices in this vector match that of `population`
     /// `k`: the K factor for ELO rating.   This is reduced by 10% every round of the tournament.
-    pub fn do_side(
+    pub fn do_side<S: Shape>(
         &self,
-        population: &Vec<Candidate<N, NSYMS>>,
+        population: &Vec<Candidate<S>>,
         remaining: &mut VecDeque<usize>,
-        rating: &mut Vec<usize>,
+        rating: &mut Vec<f64>,
         k: f64,
         rng: &mut Rando,
         score_weights: &Vec<f64>,
-    ) -> () {
+    ) -> ()
+    where
+        G: Game<S>,
+    {
         let mut k = k;
         while remaining.len() >= 2 {
             let left = remaining.pop_front().unwrap();
@@ -59,17 +63,15 @@ impl<G: Game<N, NSYMS>, const N: usize, const NSYMS: usize> SingleElimination<G,
     }
 }
 
-impl<G: Game<N, NSYMS>, const N: usize, const NSYMS: usize> Tournament<N, NSYMS>
-    for SingleElimination<G, N, NSYMS>
-{
+impl<S: Shape, G: Game<S>> Tournament<S> for SingleElimination<G> {
     fn run(
         &self,
-        population: &Vec<Candidate<N, NSYMS>>,
+        population: &Vec<Candidate<S>>,
         rng: &mut Rando,
         score_weights: &Vec<f64>,
-    ) -> (Candidate<N, NSYMS>, Vec<usize>) {
+    ) -> (Candidate<S>, Vec<usize>) {
         let mut remaining: VecDeque<usize> = VecDeque::with_capacity(population.len());
-        let mut rating = vec![1000usize; population.len()];
+        let mut rating = vec![1000.0; population.len()];
 
         for i in 0..population.len() {
             remaining.push_back(i);
@@ -85,7 +87,10 @@ impl<G: Game<N, NSYMS>, const N: usize, const NSYMS: usize> Tournament<N, NSYMS>
             score_weights,
         );
 
-        (population[remaining.pop_front().unwrap()].clone(), rating)
+        (
+            population[remaining.pop_front().unwrap()].clone(),
+            round_ratings(&rating),
+        )
     }
 }
 
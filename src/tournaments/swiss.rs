@@ -0,0 +1,147 @@
+use crate::candidate::Candidate;
+use crate::chromosone::Shape;
+use crate::game::{Game, LeftRight};
+use crate::tournaments::{
+    elo::{elo, round_ratings},
+    Tournament,
+};
+
+#[mockall_double::double]
+use crate::rando::Rando;
+
+/**
+A Swiss-system tournament: a fixed number of rounds, where each round pairs up candidates with similar records so far.
+
+Every candidate plays in every round, so it takes `rounds * n / 2` games: more than a [SingleElimination](super::SingleElimination) at about `n` games, and far fewer than the `n * (n - 1) / 2` of a [FullSeason](super::FullSeason).   Unlike the elimination formats, every candidate plays the same number of games against opponents of a similar standard, so the ratings are meaningful all the way down the field, not just at the top.   About `log2(n)` rounds are enough to find a clear winner; more rounds give better ratings.
+
+The first round is paired at random.   After that the candidates are ordered by the number of games they have won and paired off down the order, skipping opponents they have already played where possible.   With an odd number of candidates, before the pairing the lowest candidate in the order that hasn't had a bye yet sits out the round and is given the win, so no candidate gets a second free win until everyone has had one.   The ratings are ELO ratings updated after every game, and the winner is the candidate with the most wins, with ties broken by rating.
+ */
+pub struct Swiss<G> {
+    pub game: G,
+    pub rounds: usize,
+}
+
+impl<G> Swiss<G> {
+    pub const fn new(game: G, rounds: usize) -> Swiss<G> {
+        Swiss { game, rounds }
+    }
+}
+
+impl<S: Shape, G: Game<S>> Tournament<S> for Swiss<G> {
+    fn run(
+        &self,
+        population: &Vec<Candidate<S>>,
+        rng: &mut Rando,
+        score_weights: &Vec<f64>,
+    ) -> (Candidate<S>, Vec<usize>) {
+        let n = population.len();
+        let mut wins = vec![0usize; n];
+        let mut rating = vec![1000.0; n];
+        let mut played = vec![vec![false; n]; n];
+        let mut had_bye = vec![false; n];
+        let mut order: Vec<usize> = (0..n).collect();
+        rng.shuffle(&mut order);
+        let mut k = 200.0;
+
+        for _ in 0..self.rounds {
+            // stable, so the random order breaks ties in the first round and keeps things mixed up after
+            order.sort_by(|a, b| wins[*b].cmp(&wins[*a]));
+            let mut unpaired = order.clone();
+            if n % 2 == 1 {
+                let i = bye(&unpaired, &had_bye);
+                let bye = unpaired.remove(i);
+                had_bye[bye] = true;
+                wins[bye] += 1;
+            }
+            while unpaired.len() >= 2 {
+                let left = unpaired.remove(0);
+                let opponent = unpaired.iter().position(|r| !played[left][*r]).unwrap_or(0);
+                let right = unpaired.remove(opponent);
+                played[left][right] = true;
+                played[right][left] = true;
+
+                match self
+                    .game
+                    .run(&population[left], &population[right], rng, score_weights)
+                {
+                    LeftRight::Left => {
+                        wins[left] += 1;
+                        (rating[left], rating[right]) = elo(k, rating[left], rating[right]);
+                    }
+                    LeftRight::Right => {
+                        wins[right] += 1;
+                        (rating[right], rating[left]) = elo(k, rating[right], rating[left]);
+                    }
+                }
+            }
+            k *= 0.9;
+        }
+
+        let winner = (0..n)
+            .max_by(|a, b| {
+                wins[*a]
+                    .cmp(&wins[*b])
+                    .then(rating[*a].total_cmp(&rating[*b]))
+            })
+            .unwrap();
+        (population[winner].clone(), round_ratings(&rating))
+    }
+}
+
+/// the position in `order` of the candidate that sits out the round: the lowest one that hasn't had a bye, or the lowest if everyone has
+fn bye(order: &[usize], had_bye: &[bool]) -> usize {
+    order
+        .iter()
+        .rposition(|i| !had_bye[*i])
+        .unwrap_or(order.len() - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chromosone::Fixed;
+    use crate::game::Full;
+
+    #[test]
+    fn test_swiss() {
+        let pop: Vec<Candidate<Fixed<5, 3>>> = (0..4)
+            .map(|g| Candidate {
+                chromosone: [g; 5],
+                scores: vec![3.0 - g as f64],
+                violations: 0,
+            })
+            .collect();
+        let mut r = Rando::default();
+        r.expect_shuffle().times(1).return_const(());
+        let t = Swiss::new(Full::new(), 2);
+        // round 1 is 0 v 1 and 2 v 3, round 2 the winners 0 v 2 and the losers 1 v 3
+        let (winner, ratings) = t.run(&pop, &mut r, &vec![1.0]);
+        Candidate::assert_eq(&winner, &pop[0]);
+        assert_eq!(ratings, [1190, 990, 1010, 810]);
+    }
+
+    #[test]
+    fn test_swiss_bye() {
+        let pop: Vec<Candidate<Fixed<5, 3>>> = (0..3)
+            .map(|g| Candidate {
+                chromosone: [g; 5],
+                scores: vec![g as f64],
+                violations: 0,
+            })
+            .collect();
+        let mut r = Rando::default();
+        r.expect_shuffle().times(1).return_const(());
+        let t = Swiss::new(Full::new(), 2);
+        // round 1: 1 beats 0 and 2 has a bye.   round 2: 2 beats 1, who it hasn't played, and 0 has a bye
+        let (winner, ratings) = t.run(&pop, &mut r, &vec![1.0]);
+        Candidate::assert_eq(&winner, &pop[2]);
+        assert_eq!(ratings, [900, 985, 1115]);
+    }
+
+    #[test]
+    fn test_bye() {
+        // 4 is lowest but has had a bye, so 2 sits out
+        assert_eq!(bye(&[0, 2, 4], &[false, true, false, true, true]), 1);
+        assert_eq!(bye(&[0, 2, 4], &[true; 5]), 2);
+    }
+}